@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use console::style;
+use indicatif::ProgressBar;
+use rig::message::Message;
+
+use crate::{
+    ai::sources,
+    attachments::{
+        media::{self, Attachment},
+        stdin::Piped,
+        FileContext,
+    },
+    client::ChatResponse,
+    config::Config,
+    provider::Provider,
+    tools::BuiltinTools,
+};
+
+/// A prompt for a single request, with files and piped input attached
+pub struct OneShot {
+    pub provider: Provider,
+    pub model_id: String,
+    pub text: String,
+    pub attachments: Vec<Attachment>,
+}
+
+impl OneShot {
+    /// Nothing is printed to stdout, skipped files are reported on stderr
+    pub fn new(
+        cfg: &Config,
+        prompt: &str,
+        search: bool,
+        files: &[PathBuf],
+        stdin: Option<&Piped>,
+        with_media: bool,
+    ) -> Result<Self, String> {
+        let (provider, model_id) = match search {
+            true => {
+                let provider = cfg.active_search_provider().ok_or(
+                    "No search model configured. Run `termai model set --search <id>` first",
+                )?;
+                let model_id = provider.search_model().unwrap_or_default();
+                (provider.clone(), model_id)
+            }
+            false => {
+                let provider = cfg
+                    .active_provider()
+                    .ok_or("No provider configured. Run `termai provider add <name>` first")?;
+                (provider.clone(), provider.completion_model())
+            }
+        };
+
+        let mut context = FileContext::collect(files, with_media);
+        if let Some(piped) = stdin {
+            context.add_piped(piped);
+        }
+
+        for (path, reason) in &context.skipped {
+            eprintln!("{} Skipped {} ({reason})", style("✗").red(), path.display());
+        }
+        if let Some(note) = context.omitted_note() {
+            eprintln!("{} {note}", style("✗").red());
+        }
+
+        media::check_supported(&context.media, &provider.name(), &model_id)?;
+
+        Ok(Self {
+            text: context.apply(prompt),
+            attachments: context.media,
+            provider,
+            model_id,
+        })
+    }
+
+    pub fn message(&self) -> Message {
+        media::user_message(
+            &self.text,
+            &self.attachments,
+            &self.provider.name(),
+            &self.model_id,
+        )
+    }
+}
+
+/// Send a single prompt and return the answer
+pub async fn ask(
+    cfg: &Config,
+    prompt: String,
+    search: bool,
+    files: Vec<PathBuf>,
+    stdin: Option<Piped>,
+) -> Result<ChatResponse, String> {
+    let one_shot = OneShot::new(cfg, &prompt, search, &files, stdin.as_ref(), true)?;
+
+    // Tools are left out since their confirmation prompts need a terminal
+    one_shot
+        .provider
+        .chat(
+            one_shot.message(),
+            vec![],
+            &mut vec![],
+            &BuiltinTools::default(),
+            &ProgressBar::hidden(),
+            search,
+        )
+        .await
+        .map(|response| ChatResponse {
+            text: sources::append_sources(&response.text, &response.citations),
+            ..response
+        })
+        .map_err(|e| e.with_hint())
+}
@@ -1,33 +1,83 @@
-use std::{
-    io::{stdout, Write},
-    time::Duration,
-};
+use std::{fs, path::Path, time::Duration};
 
-use console::{strip_ansi_codes, style, Term};
+use console::{style, Term};
 use futures::StreamExt;
-use indicatif::{ProgressBar, TermLike};
-use rig::message::Message;
+use indicatif::ProgressBar;
 use termimad::MadSkin;
-use textwrap::wrap;
 
 use crate::{
-    ai::utils::{
-        on_the_fly_change_model, on_the_fly_select_mcp_client, NO_MODELS_FOUND_MSG,
-        NO_SEARCH_MODELS_FOUND_MSG,
+    ai::{
+        code_blocks,
+        compact::compact,
+        compare::{self, Comparison, Request},
+        render::{self, StreamRenderer},
+        sources::{self, print_sources, Source},
+        status::{print_status, print_tools},
+        utils::{
+            change_model, on_the_fly_change_model, on_the_fly_select_mcp_client,
+            provider_for_model, toggle_mcp_client, NO_MODELS_FOUND_MSG, NO_SEARCH_MODELS_FOUND_MSG,
+        },
+        ChatOptions,
+    },
+    attachments::{
+        self,
+        media::{self, Attachment},
+        FileContext,
     },
-    client::StreamingContent,
+    client::{ChatError, ChatResponse, StreamingContent},
     config::Config,
-    editor::{CommandHint, Editor},
-    utils::console::get_spinner_style,
+    editor::{complete_paths, complete_values, CommandHint, Editor},
+    provider::{llm_models, Provider},
+    session::{
+        compact::{estimate_tokens, KEEP_RECENT_ENTRIES},
+        export::{self, ExportFormat},
+        print_entries,
+        turns::Exchange,
+        Entry, Session,
+    },
+    templates::Template,
+    utils::{
+        console::{format_error, get_spinner_style},
+        enums::ProviderName,
+        interrupt::Generation,
+    },
 };
 
-pub async fn chat(
-    term: &Term,
-    cfg: &mut Config,
-    mut initial_message: Option<String>,
-    select_model: bool,
-    mut search: Option<bool>,
-) {
+/// Appended to an answer that was interrupted
+const INTERRUPTED_MARKER: &str = "*[Interrupted]*";
+
+/// Number of entries that are printed when a session is resumed
+const RESUME_PREVIEW_ENTRIES: usize = 6;
+
+/// The last exchange, taken out of the session to be sent again
+struct Resend {
+    exchange: Exchange,
+    /// Model given with `/retry --model`, used for this answer only
+    provider: Option<Provider>,
+    /// The previous answer is kept as an alternate (`/retry`, not `/edit`)
+    keep_answer: bool,
+}
+
+pub async fn chat(term: &Term, cfg: &mut Config, options: ChatOptions) {
+    let ChatOptions {
+        mut initial_message,
+        select_model,
+        search,
+        resume,
+        incognito,
+        files,
+        stdin,
+    } = options;
+    let mut pending_files = files;
+    let mut pending_stdin = stdin;
+    let mut pending_media: Vec<Attachment> = vec![];
+    let mut pending_comparison: Option<Comparison> = None;
+    // Shown again with /sources
+    let mut last_sources: Vec<Source> = vec![];
+    // A failed summary isn't retried automatically until the next /compact
+    let mut compaction_failed = false;
+    let mut search = Some(search);
+
     let mut provider = cfg
         .active_provider()
         .unwrap_or_else(|| {
@@ -53,15 +103,31 @@ pub async fn chat(
     let ai = style("AI:").bold().green();
     let user = style("You:").bold().cyan();
 
-    let skin = MadSkin::default();
+    let skin = render::skin();
     let mut spinner: ProgressBar;
     let spinner_style = get_spinner_style();
     let mut streaming = cfg.streaming();
 
-    // In memory message history
-    let mut messages = vec![];
+    let mut session = match resume {
+        Some(id) => {
+            let session = match id {
+                Some(id) => Session::load(&id),
+                None => Session::latest().ok_or("No saved sessions found".to_string()),
+            };
 
-    if initial_message.is_none() {
+            let mut session = session.unwrap_or_else(|e| {
+                eprintln!("{} {e}", style("✗").red());
+                std::process::exit(1);
+            });
+            session.set_incognito(incognito);
+            session
+        }
+        None => Session::new(&provider.completion_model(), incognito),
+    };
+
+    if !session.is_empty() {
+        print_resumed_session(&session, &skin);
+    } else if initial_message.is_none() {
         println!(
             "\n 🚀 {}: Type {} to see available commands\n\n{ai}\nWhat can I help with?\n",
             style("Quick Tip").bold().underlined(),
@@ -71,13 +137,26 @@ pub async fn chat(
         println!();
     }
 
-    let Ok(mut editor) = Editor::new(hints()) else {
+    // Built-in commands win over user commands with the same name
+    let builtin = hints(cfg, &[])
+        .iter()
+        .map(|hint| hint.display().trim().to_string())
+        .collect::<Vec<_>>();
+    let templates = Template::load_all()
+        .into_iter()
+        .filter(|template| !builtin.contains(&format!("/{}", template.name)))
+        .collect::<Vec<_>>();
+
+    // Incognito chats leave no trace on disk, input history included
+    let history = (!incognito).then_some("chat");
+    let Ok(mut editor) = Editor::new(hints(cfg, &templates), history) else {
         eprintln!("Failed to create editor");
         std::process::exit(1);
     };
 
     loop {
         let mut search = search.take().unwrap_or_default();
+        let mut resend: Option<Resend> = None;
 
         spinner = ProgressBar::new_spinner();
         spinner.set_style(spinner_style.clone());
@@ -85,54 +164,341 @@ pub async fn chat(
         println!("{user}");
 
         // Get user input
-        let mut input = String::new();
-        if let Some(message) = initial_message.take() {
+        let mut input = if let Some(message) = initial_message.take() {
             println!("{message}");
-            input = message;
+            message
         } else {
             println!();
-            while input.trim().is_empty() {
+            loop {
                 term.clear_last_lines(1).expect("Failed to clear last line");
-                input.clear();
-                input = editor.readline().unwrap_or("/quit".into());
+                match editor.readline() {
+                    Ok(line) if line.trim().is_empty() => {}
+                    Ok(line) => {
+                        // Only typed lines are saved. Before trimming, a
+                        // leading space keeps the line out of the history
+                        let _ = editor.append_history(&line);
+                        break line;
+                    }
+                    // Ctrl-D, Ctrl-C or a failed read end the chat
+                    Err(_) => break "/quit".into(),
+                }
             }
-        }
+        };
 
         input = input.trim().to_string();
 
-        let _ = editor.append_history(&input);
+        // User commands expand to a prompt, optionally for another model
+        let mut template_provider = None;
+        if let Some((template, args)) = find_template(&templates, &input) {
+            input = match template.expand(args) {
+                Ok(prompt) => prompt,
+                Err(e) => {
+                    println!("\n{} {e}\n", style("✗").red());
+                    continue;
+                }
+            };
+
+            if let Some(model) = &template.model {
+                let Some(p) = provider_for_model(cfg, model) else {
+                    println!(
+                        "\n{} Model '{model}' of /{} not found. Run /model to see the available models\n",
+                        style("✗").red(),
+                        template.name
+                    );
+                    continue;
+                };
+                template_provider = Some(p);
+            }
+        }
+
+        // Several models answer the prompt, or else the next message
+        if input.starts_with("/compare") {
+            let args = input.trim_start_matches("/compare").trim();
+            let (columns, args) = match args.strip_prefix("--columns") {
+                Some(args) => (true, args.trim_start()),
+                None => (false, args),
+            };
+            let (models, prompt) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+
+            let providers = match compare::providers(cfg, models) {
+                Ok(providers) => providers,
+                Err(e) => {
+                    println!("\n{} {e}\n", style("✗").red());
+                    continue;
+                }
+            };
+            let prompt = prompt.trim().to_string();
+            if prompt.is_empty() {
+                let models = providers
+                    .iter()
+                    .map(|p| p.completion_model())
+                    .collect::<Vec<_>>();
+                println!(
+                    "\n{} Your next message goes to {}\n",
+                    style("✔").green(),
+                    models.join(", ")
+                );
+            }
+
+            pending_comparison = Some(Comparison { providers, columns });
+            if prompt.is_empty() {
+                continue;
+            }
+            input = prompt;
+        }
 
         if input.starts_with("/model") {
+            let model = input.trim_start_matches("/model").trim();
             println!();
-            match on_the_fly_change_model(cfg, Some(provider.completion_model()), false).await {
-                Some(p) => provider = p,
-                None => println!("{}", style(NO_MODELS_FOUND_MSG).red()),
+            if model.is_empty() {
+                match on_the_fly_change_model(cfg, Some(provider.completion_model()), false).await {
+                    Some(p) => provider = p,
+                    None => println!("{}", style(NO_MODELS_FOUND_MSG).red()),
+                }
+            } else {
+                match change_model(cfg, model, false) {
+                    Some(p) => {
+                        println!(
+                            "{} Switched to {}",
+                            style("✔").green(),
+                            p.completion_model()
+                        );
+                        provider = p;
+                    }
+                    None => println!(
+                        "{} Model '{model}' not found. Run /model to see the available models",
+                        style("✗").red()
+                    ),
+                }
             }
             println!();
             continue;
         }
 
         if input.starts_with("/search-model") {
+            let model = input.trim_start_matches("/search-model").trim();
             println!();
-            let model_id = search_provider
-                .as_ref()
-                .and_then(|p| p.search_model().map(|m| m.to_string()));
+            if model.is_empty() {
+                let model_id = search_provider
+                    .as_ref()
+                    .and_then(|p| p.search_model().map(|m| m.to_string()));
 
-            match on_the_fly_change_model(cfg, model_id, true).await {
-                Some(p) => search_provider = Some(p),
-                None => println!("{}", style(NO_SEARCH_MODELS_FOUND_MSG).red()),
+                match on_the_fly_change_model(cfg, model_id, true).await {
+                    Some(p) => search_provider = Some(p),
+                    None => println!("{}", style(NO_SEARCH_MODELS_FOUND_MSG).red()),
+                }
+            } else {
+                match change_model(cfg, model, true) {
+                    Some(p) => {
+                        let model_id = p.search_model().unwrap_or_default();
+                        println!("{} Switched to {model_id}", style("✔").green());
+                        search_provider = Some(p);
+                    }
+                    None => println!(
+                        "{} Search model '{model}' not found. Run /search-model to see the available models",
+                        style("✗").red()
+                    ),
+                }
             }
             println!();
             continue;
         }
 
+        if input.starts_with("/status") {
+            println!();
+            print_status(
+                cfg,
+                &provider,
+                search_provider.as_ref(),
+                streaming,
+                &session,
+            )
+            .await;
+            println!();
+            continue;
+        }
+
+        if input.starts_with("/tools") {
+            println!();
+            print_tools(cfg).await;
+            println!();
+            continue;
+        }
+
+        if input.starts_with("/sources") {
+            match last_sources.is_empty() {
+                true => println!(
+                    "\n{} No sources to show. Search the web with /search first\n",
+                    style("ℹ").cyan()
+                ),
+                false => {
+                    println!();
+                    print_sources(&last_sources);
+                    println!();
+                }
+            }
+            continue;
+        }
+
         if input.starts_with("/clear") | input.eq("clear") {
             term.clear_screen().expect("Failed to clear screen");
-            messages.clear();
+            session = Session::new(&provider.completion_model(), session.is_incognito());
+            last_sources.clear();
+            compaction_failed = false;
             println!("{ai}\nWhat can I help with?\n");
             continue;
         }
 
+        if input.starts_with("/copy") {
+            let arg = input.trim_start_matches("/copy").trim();
+            let number = match arg.parse::<usize>() {
+                Ok(number) => Some(number),
+                Err(_) if arg.is_empty() => None,
+                Err(_) => {
+                    println!("\n{} Usage: /copy [n]\n", style("✗").red());
+                    continue;
+                }
+            };
+
+            let result = match session.last_answer() {
+                Some(answer) => code_blocks::copy(answer, number),
+                None => Err("There is no answer to copy yet".to_string()),
+            };
+            print_result(result);
+            continue;
+        }
+
+        if input.starts_with("/run") {
+            let Ok(number) = input.trim_start_matches("/run").trim().parse::<usize>() else {
+                println!("\n{} Usage: /run <n>\n", style("✗").red());
+                continue;
+            };
+
+            let Some(answer) = session.last_answer() else {
+                println!(
+                    "\n{} There is no answer to run code from yet\n",
+                    style("✗").red()
+                );
+                continue;
+            };
+
+            println!();
+            let timeout = Duration::from_secs(cfg.shell_timeout_secs() as u64);
+            let output_limit = cfg.shell_output_limit_kb() as usize * 1024;
+            match code_blocks::run(answer, number, timeout, output_limit).await {
+                Ok(message) => initial_message = message,
+                Err(e) => println!("{} {e}\n", style("✗").red()),
+            }
+            continue;
+        }
+
+        if input.starts_with("/save-code") {
+            let args = input.trim_start_matches("/save-code").trim();
+            let Some((number, file)) = args
+                .split_once(' ')
+                .and_then(|(number, file)| Some((number.parse::<usize>().ok()?, file.trim())))
+                .filter(|(_, file)| !file.is_empty())
+            else {
+                println!("\n{} Usage: /save-code <n> <file>\n", style("✗").red());
+                continue;
+            };
+
+            let result = match session.last_answer() {
+                Some(answer) => code_blocks::save(answer, number, file),
+                None => Err("There is no answer to save code from yet".to_string()),
+            };
+            print_result(result);
+            continue;
+        }
+
+        if input.starts_with("/save") {
+            let title = input.trim_start_matches("/save").trim();
+
+            if !title.is_empty() {
+                session.set_title(title);
+            }
+            session.set_incognito(false);
+
+            if session.is_empty() {
+                println!("\n{} Nothing to save yet\n", style("✗").red());
+            } else if save_session(&session) {
+                println!(
+                    "\n{} Session saved as {}\n",
+                    style("✔").green(),
+                    style(&session.id).bold()
+                );
+            }
+            continue;
+        }
+
+        if input.starts_with("/compact") {
+            println!();
+            let compacted = compact(cfg, &mut session, &provider, 2).await;
+            compaction_failed = compacted.is_err();
+            print_compacted(compacted, None);
+            save_session(&session);
+            continue;
+        }
+
+        if input.starts_with("/unpin") {
+            session.unpin_all();
+            save_session(&session);
+            println!("\n{} All messages unpinned\n", style("✔").green());
+            continue;
+        }
+
+        if input.starts_with("/pin") {
+            match session.pin_last_exchange() {
+                0 => println!("\n{} Nothing to pin yet\n", style("✗").red()),
+                _ => {
+                    save_session(&session);
+                    println!(
+                        "\n{} The last exchange will never be compacted\n",
+                        style("✔").green()
+                    );
+                }
+            }
+            continue;
+        }
+
+        if input.starts_with("/export") {
+            let file = input.trim_start_matches("/export").trim();
+            if file.is_empty() {
+                println!("\n{} Please provide a file name\n", style("✗").red());
+                continue;
+            }
+
+            let path = Path::new(file);
+            let format = ExportFormat::from_path(path).unwrap_or(ExportFormat::Markdown);
+            match fs::write(path, export::export(&session, format)) {
+                Ok(_) => println!("\n{} Exported to {file}\n", style("✔").green()),
+                Err(e) => println!("\n{} Failed to write {file}: {e}\n", style("✗").red()),
+            }
+            continue;
+        }
+
+        if input.starts_with("/load") {
+            let id = input.trim_start_matches("/load").trim();
+            let loaded = match id.is_empty() {
+                true => Session::latest().ok_or("No saved sessions found".to_string()),
+                false => Session::load(id),
+            };
+
+            match loaded {
+                Ok(mut loaded) => {
+                    save_session(&session);
+                    // An incognito chat stays off the disk after switching sessions
+                    loaded.set_incognito(session.is_incognito());
+                    session = loaded;
+                    compaction_failed = false;
+                    println!();
+                    print_resumed_session(&session, &skin);
+                }
+                Err(e) => println!("\n{} {e}\n", style("✗").red()),
+            }
+            continue;
+        }
+
         if input.starts_with("/stream") || input.starts_with("/nostream") {
             streaming = input.starts_with("/stream");
             if streaming {
@@ -144,13 +510,151 @@ pub async fn chat(
         }
 
         if input.starts_with("/mcp") {
+            let name = input.trim_start_matches("/mcp").trim();
             println!();
-            on_the_fly_select_mcp_client(cfg);
+            match (name.is_empty(), toggle_mcp_client(cfg, name)) {
+                (true, _) => on_the_fly_select_mcp_client(cfg),
+                (false, Some(true)) => println!("{} {name} enabled", style("✔").green()),
+                (false, Some(false)) => println!("{} {name} disabled", style("✔").green()),
+                (false, None) => println!(
+                    "{} No MCP server named '{name}'. Run /mcp to see the configured servers",
+                    style("✗").red()
+                ),
+            }
             println!();
             continue;
         }
 
-        if input.starts_with("/search") {
+        if input.starts_with("/attach") {
+            let path = input.trim_start_matches("/attach").trim();
+            if path.is_empty() {
+                println!(
+                    "\n{} Please provide an image or PDF file\n",
+                    style("✗").red()
+                );
+                continue;
+            }
+
+            match Attachment::load(Path::new(path)) {
+                Ok(attachment) => {
+                    println!(
+                        "\n{} {} will be sent with your next message\n",
+                        style("✔").green(),
+                        attachment.name
+                    );
+                    pending_media.push(attachment);
+                }
+                Err(e) => println!("\n{} {e}\n", style("✗").red()),
+            }
+            continue;
+        }
+
+        if input.starts_with("/undo") {
+            match session.pop_exchange() {
+                Some(_) => {
+                    save_session(&session);
+                    println!("\n{} Removed the last exchange\n", style("✔").green());
+                }
+                None => println!("\n{} Nothing to undo\n", style("✗").red()),
+            }
+            continue;
+        }
+
+        if input.starts_with("/alt") {
+            match session.next_alternate() {
+                Some((position, count)) => {
+                    save_session(&session);
+                    let entry = session.entries.last().expect("Answer exists");
+                    println!(
+                        "\n{ai} {}",
+                        style(format!("(answer {position} of {count})")).dim()
+                    );
+                    render::print_answer(&skin, &entry.content);
+                    println!();
+                }
+                None => println!(
+                    "\n{} No other answers yet. Use /retry to generate one\n",
+                    style("✗").red()
+                ),
+            }
+            continue;
+        }
+
+        if input.starts_with("/retry") {
+            let args = input.trim_start_matches("/retry").trim();
+            let retry_provider = match args.strip_prefix("--model") {
+                _ if args.is_empty() => None,
+                Some(model) if !model.trim().is_empty() => {
+                    match provider_for_model(cfg, model.trim()) {
+                        Some(p) => Some(p),
+                        None => {
+                            println!(
+                                "\n{} Model '{}' not found. Run /model to see the available models\n",
+                                style("✗").red(),
+                                model.trim()
+                            );
+                            continue;
+                        }
+                    }
+                }
+                _ => {
+                    println!("\n{} Usage: /retry [--model <id>]\n", style("✗").red());
+                    continue;
+                }
+            };
+
+            let Some(exchange) = session.pop_exchange() else {
+                println!("\n{} Nothing to retry yet\n", style("✗").red());
+                continue;
+            };
+
+            // Search answers are regenerated with the search model
+            let answered_by = exchange.assistant.as_ref().and_then(|e| e.model.clone());
+            search = retry_provider.is_none()
+                && answered_by.is_some()
+                && answered_by
+                    == search_provider
+                        .as_ref()
+                        .and_then(|p| p.search_model().map(|m| m.to_string()))
+                && answered_by != Some(provider.completion_model());
+
+            input = exchange.user.content.clone();
+            resend = Some(Resend {
+                exchange,
+                provider: retry_provider,
+                keep_answer: true,
+            });
+        } else if input.starts_with("/edit") {
+            let Some(exchange) = session.pop_exchange() else {
+                println!("\n{} Nothing to edit yet\n", style("✗").red());
+                continue;
+            };
+
+            // Only the typed prompt is edited, attached files are sent again as they were
+            let (typed, context) = FileContext::split(&exchange.user.content);
+            let context = context.to_string();
+            let _ = term.clear_last_lines(1);
+            let edited = editor
+                .readline_with_initial(typed)
+                .map(|line| line.trim().to_string())
+                .unwrap_or_default();
+
+            if edited.is_empty() {
+                session.restore_exchange(exchange);
+                println!("\n{} Edit cancelled\n", style("✗").red());
+                continue;
+            }
+
+            let _ = editor.append_history(&edited);
+            input = format!("{edited}{context}");
+            resend = Some(Resend {
+                exchange,
+                provider: None,
+                keep_answer: false,
+            });
+        }
+
+        if resend.is_none() && input.starts_with("/search") {
             input = input.trim_start_matches("/search").trim().to_string();
             if input.is_empty() {
                 println!("\n{} Please provide a search query\n", style("✗").red());
@@ -159,49 +663,182 @@ pub async fn chat(
             search = true;
         }
 
-        match editor.execute_command(&input) {
-            Some(output) => input = output,
-            None => {
-                println!();
-                continue;
+        if resend.is_none() {
+            match editor.execute_command(&input) {
+                Some(output) => input = output,
+                None => {
+                    println!();
+                    continue;
+                }
             }
         }
 
         println!();
 
+        // Inline piped input, files passed with --file and files mentioned with @path
+        let mut paths = vec![];
+        let mut piped = None;
+        if resend.is_none() {
+            paths = std::mem::take(&mut pending_files);
+            paths.extend(attachments::mentions(&input));
+            piped = pending_stdin.take();
+        }
+        if !paths.is_empty() || piped.is_some() {
+            let mut context = FileContext::collect(&paths, true);
+            if let Some(piped) = &piped {
+                context.add_piped(piped);
+            }
+            if !context.is_empty() {
+                context.print_summary();
+                println!();
+            }
+            input = context.apply(&input);
+            pending_media.append(&mut context.media);
+        }
+
+        let model_override = resend
+            .as_mut()
+            .and_then(|r| r.provider.take())
+            .or(template_provider);
+        let chat_provider = match (&model_override, search) {
+            (Some(p), _) => p,
+            (_, true) => search_provider.as_ref().expect("Search provider not set"),
+            (_, false) => &provider,
+        };
+        let model_id = match search {
+            true => chat_provider.search_model().unwrap_or_default(),
+            false => chat_provider.completion_model(),
+        };
+        let provider_name = chat_provider.name();
+
+        // Compared models answer new messages, not retries and edits
+        let comparison = match resend {
+            Some(_) => None,
+            None => pending_comparison.take(),
+        };
+
+        // Images and PDFs are kept for the next message when the model can't read them
+        let staged = match &resend {
+            Some(resend) => &resend.exchange.user.attachments,
+            None => &pending_media,
+        };
+        let supported = match &comparison {
+            Some(comparison) => comparison
+                .providers
+                .iter()
+                .try_for_each(|p| media::check_supported(staged, &p.name(), &p.completion_model())),
+            None => media::check_supported(staged, &provider_name, &model_id),
+        };
+        if let Err(e) = supported {
+            println!(
+                "{} {e}. Switch models with /model and send the message again.\n",
+                style("✗").red()
+            );
+            if let Some(resend) = resend {
+                session.restore_exchange(resend.exchange);
+            }
+            continue;
+        }
+        // Put back when the new answer fails or an edit is interrupted
+        let original = resend.as_ref().map(|r| r.exchange.clone());
+        let edit = resend.as_ref().is_some_and(|r| !r.keep_answer);
+        let (attachments, previous) = match resend {
+            Some(Resend {
+                exchange,
+                keep_answer,
+                ..
+            }) => (
+                exchange.user.attachments,
+                exchange.assistant.filter(|_| keep_answer),
+            ),
+            None => (std::mem::take(&mut pending_media), None),
+        };
+
+        // Compact the history before it outgrows the context window
+        let threshold = cfg.compact_threshold() as usize;
+        let context_window = llm_models::context_window(&model_id);
+        let used = session.estimated_tokens()
+            + estimate_tokens(&input)
+            + attachments
+                .iter()
+                .map(|a| a.estimated_tokens())
+                .sum::<usize>();
+        if threshold > 0 && !compaction_failed && used * 100 >= context_window * threshold {
+            let percent = used * 100 / context_window;
+            // Once only pinned and recent entries are left there is nothing to report
+            let compacted = compact(cfg, &mut session, &provider, KEEP_RECENT_ENTRIES).await;
+            compaction_failed = compacted.is_err();
+            if !matches!(compacted, Ok(None)) {
+                print_compacted(compacted, Some(percent));
+                save_session(&session);
+            }
+        }
+
+        let requests = comparison.as_ref().map(|comparison| {
+            comparison
+                .providers
+                .iter()
+                .map(|p| {
+                    let messages = session.messages(&p.name(), &p.completion_model());
+                    Request::new(p, &input, &attachments, messages)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // Save the prompt right away so it survives an interrupted answer
+        let messages = session.messages(&provider_name, &model_id);
+        let prompt = media::user_message(&input, &attachments, &provider_name, &model_id);
+        session.push_user(&input, attachments);
+        save_session(&session);
+
         spinner.enable_steady_tick(Duration::from_millis(100));
         spinner.set_message(format!("{ai}"));
 
+        if let (Some(comparison), Some(requests)) = (comparison, requests) {
+            compare_answers(&mut session, &skin, &spinner, requests, comparison.columns).await;
+            continue;
+        }
+
+        let tools = cfg.builtin_tools();
+        let generation = Generation::start();
         if streaming {
-            let mut response = String::new();
             let mut final_response = String::new();
+            let mut renderer = StreamRenderer::new(&skin, term);
 
-            let mut stream = if search {
-                search_provider
-                    .as_ref()
-                    .expect("Search provider not set")
-                    .chat_stream(&input, messages.clone(), cfg.mcp_clients(), true)
-                    .await
-            } else {
-                provider
-                    .chat_stream(&input, messages.clone(), cfg.mcp_clients(), false)
-                    .await
-            };
+            let mut stream = chat_provider
+                .chat_stream(prompt, messages.clone(), cfg.mcp_clients(), &tools, search)
+                .await;
 
-            let mut line_count = 0;
+            let mut tool_calls = vec![];
+            let mut citations = vec![];
+            let mut answer_sources = vec![];
+            let mut error = None;
 
             let _ = term.hide_cursor();
 
             let mut clear = true;
-            while let Some(content) = stream.next().await {
+            loop {
+                let content = tokio::select! {
+                    content = stream.next() => content,
+                    _ = generation.interrupted() => break,
+                };
+                let Some(content) = content else {
+                    break;
+                };
+
                 let content = match content {
                     Ok(content) => content,
-                    Err(e) => StreamingContent::Text(e.to_string()),
+                    Err(e) => {
+                        error = Some(e);
+                        break;
+                    }
                 };
 
                 let content = match content {
                     StreamingContent::Text(text) => text,
                     StreamingContent::PauseSpinner => {
+                        // Nothing is redrawn across the confirmation prompt
+                        renderer.finish();
                         spinner.disable_steady_tick();
                         continue;
                     }
@@ -216,79 +853,358 @@ pub async fn chat(
                         clear = true;
                         continue;
                     }
+                    StreamingContent::ToolCall(tool_call) => {
+                        tool_calls.push(tool_call);
+                        continue;
+                    }
+                    StreamingContent::Citations(list) => {
+                        citations = list;
+                        continue;
+                    }
+                    StreamingContent::Done(..) => continue,
                 };
 
-                let term_width = term.width() as usize;
-
                 if clear {
                     clear = false;
                     spinner.finish_and_clear();
                     println!("{ai}");
                 }
 
-                response.push_str(&content);
                 final_response.push_str(&content);
+                // The answer comes in one piece after its citations
+                let content = match citations.is_empty() {
+                    true => content,
+                    false => {
+                        let (cited, found) = sources::cite(&content, &citations);
+                        citations.clear();
+                        answer_sources = found;
+                        cited
+                    }
+                };
+                renderer.push(&content);
+            }
 
-                let text = format!("{}", skin.text(&response, Some(term_width)));
-
-                if line_count > 0 {
-                    clear_lines(line_count);
+            // Stops a request or tool call that is still running
+            drop(stream);
+            if let (Some(e), true) = (&error, final_response.is_empty()) {
+                spinner.finish_and_clear();
+                let _ = term.show_cursor();
+                println!("{}\n", format_error(e));
+                drop_unanswered(&mut session, original);
+                continue;
+            }
+            if generation.is_interrupted() || error.is_some() {
+                if clear {
+                    spinner.finish_and_clear();
+                    println!("{ai}");
                 }
+                let marker = interrupted_marker(&final_response);
+                renderer.push(&marker);
+                final_response.push_str(&marker);
+            }
+            renderer.finish();
+            println!();
+            if let Some(e) = &error {
+                println!("{}\n", format_error(e));
+            }
+            if !answer_sources.is_empty() {
+                print_sources(&answer_sources);
+                println!();
+            }
+            if search {
+                last_sources = answer_sources;
+            }
+            let _ = term.show_cursor();
 
-                println!("{}", text);
-
-                if response.ends_with("\n\n") {
-                    response.clear();
-                    line_count = 1;
-                } else {
-                    line_count = count_wrapped_lines(&text, term_width);
-                }
+            // An edit that didn't get a full answer doesn't replace the original
+            if edit && (generation.is_interrupted() || error.is_some()) {
+                drop_unanswered(&mut session, original);
+                continue;
             }
 
-            messages.push(Message::user(&input));
-            messages.push(Message::assistant(final_response));
+            session.push_assistant(&final_response, &model_id, tool_calls);
+            print_alternates(&mut session, previous);
+            save_session(&session);
 
-            let _ = term.show_cursor();
             let _ = term.flush();
         } else {
-            let response = if search {
-                search_provider
-                    .as_ref()
-                    .expect("Search provider not set")
-                    .chat(&input, messages.clone(), cfg.mcp_clients(), &spinner, true)
-                    .await
-            } else {
-                provider
-                    .chat(&input, messages.clone(), cfg.mcp_clients(), &spinner, false)
-                    .await
+            let response = tokio::select! {
+                response = chat_provider.chat(
+                    prompt,
+                    messages.clone(),
+                    cfg.mcp_clients(),
+                    &tools,
+                    &spinner,
+                    search,
+                ) => response,
+                _ = generation.interrupted() => Ok(ChatResponse {
+                    text: interrupted_marker(""),
+                    ..Default::default()
+                }),
             };
 
-            let response = match response {
+            let ChatResponse {
+                text: response,
+                tool_calls,
+                citations,
+                ..
+            } = match response {
                 Ok(response) => response,
-                Err(e) => e.to_string(),
+                Err(e) => {
+                    spinner.finish_and_clear();
+                    println!("{}\n", format_error(&e));
+                    drop_unanswered(&mut session, original);
+                    continue;
+                }
             };
 
             spinner.finish_and_clear();
             println!("{ai}");
-            skin.print_text(&response);
+            let (cited, answer_sources) = sources::cite(&response, &citations);
+            render::print_answer(&skin, &cited);
             println!();
+            if !answer_sources.is_empty() {
+                print_sources(&answer_sources);
+                println!();
+            }
+            if search {
+                last_sources = answer_sources;
+            }
 
-            messages.push(Message::user(&input));
-            messages.push(Message::assistant(response));
+            if edit && generation.is_interrupted() {
+                drop_unanswered(&mut session, original);
+                continue;
+            }
+
+            session.push_assistant(&response, &model_id, tool_calls);
+            print_alternates(&mut session, previous);
+            save_session(&session);
         }
     }
 }
 
-fn hints() -> Vec<CommandHint> {
-    vec![
+/// Show the answers of the compared models and keep the chosen one in the
+/// history, with the others as alternates
+async fn compare_answers(
+    session: &mut Session,
+    skin: &MadSkin,
+    spinner: &ProgressBar,
+    requests: Vec<Request>,
+    columns: bool,
+) {
+    let generation = Generation::start();
+    let answers = tokio::select! {
+        answers = compare::run(requests) => Some(answers),
+        _ = generation.interrupted() => None,
+    };
+    drop(generation);
+    spinner.finish_and_clear();
+
+    let Some(answers) = answers else {
+        session.pop_exchange();
+        save_session(session);
+        println!(
+            "{} The comparison was interrupted, your message was removed\n",
+            style("✗").red()
+        );
+        return;
+    };
+
+    compare::print_answers(skin, &answers, columns);
+
+    let Some(chosen) = compare::choose(&answers) else {
+        session.pop_exchange();
+        save_session(session);
+        println!(
+            "\n{} {}\n",
+            style("ℹ").cyan(),
+            style("No answer was kept, your message was removed from the conversation.").dim()
+        );
+        return;
+    };
+
+    let answer = &answers[chosen];
+    if let Ok(response) = &answer.response {
+        session.push_assistant(
+            &response.text,
+            &answer.model_id(),
+            response.tool_calls.clone(),
+        );
+    }
+    for (i, answer) in answers.iter().enumerate() {
+        if let (Ok(response), false) = (&answer.response, i == chosen) {
+            session.add_alternate(
+                &response.text,
+                &answer.model_id(),
+                response.tool_calls.clone(),
+            );
+        }
+    }
+    save_session(session);
+
+    let count = answers.iter().filter(|a| a.response.is_ok()).count();
+    println!(
+        "\n{} {}\n",
+        style("ℹ").cyan(),
+        style(format!(
+            "Answer 1 of {count} kept. Use /alt to switch between answers."
+        ))
+        .dim()
+    );
+}
+
+/// Marks an answer that was cut off with Ctrl-C, so the model knows it is
+/// incomplete. An open code block is closed first.
+fn interrupted_marker(partial: &str) -> String {
+    let mut marker = String::new();
+    if code_blocks::scan(partial).1 {
+        marker.push_str("\n```");
+    }
+    if !partial.is_empty() {
+        marker.push_str("\n\n");
+    }
+    marker.push_str(INTERRUPTED_MARKER);
+    marker
+}
+
+/// Take a message without a full answer out of the history. A retried or
+/// edited exchange is put back as it was.
+fn drop_unanswered(session: &mut Session, original: Option<Exchange>) {
+    session.pop_exchange();
+    let message = match original {
+        Some(original) => {
+            session.restore_exchange(original);
+            "Your earlier message and its answer were put back."
+        }
+        None => "Your message was removed from the conversation. Press Up to send it again.",
+    };
+    save_session(session);
+    println!("{} {}\n", style("ℹ").cyan(), style(message).dim());
+}
+
+/// Keep the answer that was retried and tell the user how to get it back
+fn print_alternates(session: &mut Session, previous: Option<Entry>) {
+    let Some(previous) = previous else {
+        return;
+    };
+
+    session.set_alternates(previous);
+    let count = session
+        .entries
+        .last()
+        .map_or(1, |entry| entry.alternates.len() + 1);
+    println!(
+        "{} {}\n",
+        style("ℹ").cyan(),
+        style(format!(
+            "Answer {count} of {count}. Use /alt to switch between answers."
+        ))
+        .dim()
+    );
+}
+
+/// The user command typed at the start of `input` and its arguments
+fn find_template<'t, 'i>(
+    templates: &'t [Template],
+    input: &'i str,
+) -> Option<(&'t Template, &'i str)> {
+    let (command, args) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+    let name = command.strip_prefix('/')?;
+    templates
+        .iter()
+        .find(|template| template.name == name)
+        .map(|template| (template, args.trim()))
+}
+
+fn hints(cfg: &Config, templates: &[Template]) -> Vec<CommandHint> {
+    // Models complete by ID and by display name
+    let models = |models: &[(ProviderName, String, String)]| {
+        models
+            .iter()
+            .flat_map(|(_, id, name)| [id.clone(), name.clone()])
+            .collect::<Vec<_>>()
+    };
+    let completion_models = models(cfg.available_completion_models());
+    // A space ends the list of compared models, so display names can't be used
+    let compare_models = cfg
+        .available_completion_models()
+        .iter()
+        .map(|(_, id, _)| id.clone())
+        .collect::<Vec<_>>();
+    let search_models = models(cfg.available_search_models());
+    let mcp_clients = cfg.mcp_client_names();
+
+    let commands = templates
+        .iter()
+        .map(|template| {
+            let description = template
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("{}.md", template.name));
+            (format!("/{}", template.name), description)
+        })
+        .collect::<Vec<_>>();
+
+    let mut hints = templates
+        .iter()
+        .map(|template| {
+            let command = match template.takes_args() {
+                true => format!("/{} ", template.name),
+                false => format!("/{}", template.name),
+            };
+            CommandHint::new(&command, &command, Box::new(|_| None))
+        })
+        .collect::<Vec<_>>();
+
+    hints.extend(vec![
         // Handled with custom logic (due to needing outside references)
-        CommandHint::new("/model", "/model", Box::new(|_| None)),
-        CommandHint::new("/search-model", "/search-model", Box::new(|_| None)),
+        CommandHint::new("/model", "/model", Box::new(|_| None))
+            .with_args(move |typed| (0, complete_values(&completion_models, typed))),
+        CommandHint::new("/search-model", "/search-model", Box::new(|_| None))
+            .with_args(move |typed| (0, complete_values(&search_models, typed))),
         CommandHint::new("/search ", "/search ", Box::new(|_| None)),
+        CommandHint::new("/sources", "/sources", Box::new(|_| None)),
+        CommandHint::new("/compare ", "/compare ", Box::new(|_| None)).with_args(move |typed| {
+            let models = typed.strip_prefix("--columns").map_or(typed, str::trim_start);
+            if models.contains(char::is_whitespace) {
+                return (typed.len(), vec![]);
+            }
+            let start = models.rfind(',').map_or(0, |i| i + 1);
+            let offset = typed.len() - models.len() + start;
+            (offset, complete_values(&compare_models, &models[start..]))
+        }),
+        CommandHint::new("/attach ", "/attach ", Box::new(|_| None)).with_args(complete_paths),
         CommandHint::new("/clear", "/clear", Box::new(|_| None)),
+        CommandHint::new("/status", "/status", Box::new(|_| None)),
+        CommandHint::new("/tools", "/tools", Box::new(|_| None)),
         CommandHint::new("/stream", "/stream", Box::new(|_| None)),
         CommandHint::new("/nostream", "/nostream", Box::new(|_| None)),
-        CommandHint::new("/mcp", "/mcp", Box::new(|_| None)),
+        CommandHint::new("/mcp", "/mcp", Box::new(|_| None))
+            .with_args(move |typed| (0, complete_values(&mcp_clients, typed))),
+        CommandHint::new("/save", "/save", Box::new(|_| None)),
+        CommandHint::new("/save-code ", "/save-code ", Box::new(|_| None)).with_args(|typed| {
+            // The path comes after the block number
+            match typed.split_once(char::is_whitespace) {
+                Some((n, file)) if n.parse::<usize>().is_ok() => {
+                    let file = file.trim_start();
+                    let (offset, candidates) = complete_paths(file);
+                    (typed.len() - file.len() + offset, candidates)
+                }
+                _ => (typed.len(), vec![]),
+            }
+        }),
+        CommandHint::new("/load ", "/load ", Box::new(|_| None)),
+        CommandHint::new("/export ", "/export ", Box::new(|_| None)).with_args(complete_paths),
+        CommandHint::new("/compact", "/compact", Box::new(|_| None)),
+        CommandHint::new("/pin", "/pin", Box::new(|_| None)),
+        CommandHint::new("/unpin", "/unpin", Box::new(|_| None)),
+        CommandHint::new("/retry", "/retry", Box::new(|_| None)),
+        CommandHint::new("/edit", "/edit", Box::new(|_| None)),
+        CommandHint::new("/editor", "/editor", Box::new(|_| None)),
+        CommandHint::new("/undo", "/undo", Box::new(|_| None)),
+        CommandHint::new("/alt", "/alt", Box::new(|_| None)),
+        CommandHint::new("/copy", "/copy", Box::new(|_| None)),
+        CommandHint::new("/run ", "/run ", Box::new(|_| None)),
         // Handled dynamically
         CommandHint::new(
             "/quit",
@@ -301,53 +1217,189 @@ fn hints() -> Vec<CommandHint> {
         CommandHint::new(
             "/help",
             "/help",
-            Box::new(|_| {
+            Box::new(move |_| {
                 let s = |s: String| style(s).bold();
                 println!("\n{}", style("Prompt Commands:").bold().underlined());
                 println!(
                     "  {}       - Search the web with the active search model",
                     s("/search".into())
                 );
+                println!(
+                    "  {}        - Attach a file or directory (anywhere in the prompt)",
+                    s("@path".into())
+                );
+                println!(
+                    "  {}       - Write the message in $VISUAL or $EDITOR (also Ctrl-X Ctrl-E)",
+                    s("/editor".into())
+                );
                 println!("\n{}", style("Other Commands:").bold().underlined());
                 println!(
-                    "  {}        - Change the active completion model",
+                    "  {}       - Send an image (PNG, JPEG, WebP) or PDF with the next message",
+                    s("/attach".into())
+                );
+                println!(
+                    "  {}        - Change the active completion model, or switch to the given one",
                     s("/model".into())
                 );
                 println!(
-                    "  {} - Change the active search model",
+                    "  {} - Change the active search model, or switch to the given one",
                     s("/search-model".into())
                 );
+                println!(
+                    "  {}      - Send a prompt (or the next message) to several models, e.g. gpt-4.1,o4-mini, and keep one answer",
+                    s("/compare".into())
+                );
+                println!(
+                    "  {}       - Show the models, MCP servers, tools and context usage",
+                    s("/status".into())
+                );
+                println!(
+                    "  {}        - List the tools the model can use and their inputs",
+                    s("/tools".into())
+                );
+                println!(
+                    "  {}      - Show the sources of the last search answer again",
+                    s("/sources".into())
+                );
                 println!("  {}       - Enable streaming", s("/stream".into()));
                 println!("  {}     - Disable streaming", s("/nostream".into()));
                 println!(
-                    "  {}          - Select active MCP servers",
+                    "  {}          - Select active MCP servers, or turn the given one on or off",
                     s("/mcp".into())
                 );
                 println!(
-                    "  {}        - Clear the screen and chat history",
+                    "  {}        - Clear the screen and start a new session",
                     s("/clear".into())
                 ); // /clear
+                println!(
+                    "  {}         - Save the session now, optionally with a new title (also ends incognito mode)",
+                    s("/save".into())
+                );
+                println!(
+                    "  {}         - Load a saved session by ID (the most recent if no ID is given)",
+                    s("/load".into())
+                );
+                println!(
+                    "  {}       - Export the session to a .md, .json or .html file",
+                    s("/export".into())
+                );
+                println!(
+                    "  {}      - Summarize older messages to free up the context window",
+                    s("/compact".into())
+                );
+                println!(
+                    "  {}          - Pin the last exchange so it is never compacted",
+                    s("/pin".into())
+                );
+                println!("  {}        - Unpin all messages", s("/unpin".into()));
+                println!(
+                    "  {}        - Regenerate the last answer, optionally with --model <id>",
+                    s("/retry".into())
+                );
+                println!(
+                    "  {}         - Rewrite your last message and send it again",
+                    s("/edit".into())
+                );
+                println!(
+                    "  {}         - Remove the last message and its answer",
+                    s("/undo".into())
+                );
+                println!(
+                    "  {}          - Switch between regenerated answers",
+                    s("/alt".into())
+                );
+                println!(
+                    "  {}         - Copy code block n of the last answer (the whole answer without n)",
+                    s("/copy".into())
+                );
+                println!(
+                    "  {} - Save code block n of the last answer to a file",
+                    s("/save-code n file".into())
+                );
+                println!(
+                    "  {}          - Run code block n in your shell and optionally send the output",
+                    s("/run".into())
+                );
                 println!("  {}         - Exit TermAI", s("/quit".into()));
                 println!("  {}         - Show this help message", s("/help".into()));
+
+                if !commands.is_empty() {
+                    let width = commands.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+                    println!("\n{}", style("Your Commands:").bold().underlined());
+                    for (command, description) in &commands {
+                        println!("  {} - {description}", s(format!("{command:width$}")));
+                    }
+                }
                 None
             }),
         ),
-    ]
+    ]);
+
+    hints
 }
 
-fn count_wrapped_lines(rendered: &str, width: usize) -> usize {
-    let plain_text = strip_ansi_codes(rendered);
+fn print_compacted(compacted: Result<Option<usize>, ChatError>, percent: Option<usize>) {
+    let info = style("ℹ").cyan();
+
+    if let Some(percent) = percent {
+        println!(
+            "{info} {}",
+            style(format!(
+                "The conversation is using about {percent}% of the context window."
+            ))
+            .dim()
+        );
+    }
+
+    match compacted {
+        Ok(Some(entries)) => println!(
+            "{info} {}\n",
+            style(format!("Summarized {entries} older messages to make room.")).dim()
+        ),
+        Ok(None) => println!(
+            "{info} {}\n",
+            style("Nothing to compact. Pinned and recent messages are always kept.").dim()
+        ),
+        Err(e) => {
+            let message =
+                format!("Could not summarize older messages, they are kept as they are: {e}");
+            println!("{}\n", format_error(&ChatError::new(e.kind, &message)));
+        }
+    }
+}
 
-    let wrapped_lines = wrap(&plain_text, width);
+fn print_result(result: Result<String, String>) {
+    match result {
+        Ok(message) => println!("\n{} {message}\n", style("✔").green()),
+        Err(e) => println!("\n{} {e}\n", style("✗").red()),
+    }
+}
 
-    wrapped_lines.len()
+fn save_session(session: &Session) -> bool {
+    match session.save() {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("{} {e}", style("✗").red());
+            false
+        }
+    }
 }
 
-fn clear_lines(num_lines: usize) {
-    let mut out = stdout();
-    for _ in 0..num_lines {
-        let _ = write!(out, "\x1B[A"); // Move cursor up
-        let _ = write!(out, "\x1B[2K"); // Clear line
+fn print_resumed_session(session: &Session, skin: &MadSkin) {
+    println!(
+        "{} {} {}\n",
+        style("Resumed session:").bold(),
+        style(&session.title).cyan(),
+        style(format!("({})", session.id)).dim()
+    );
+
+    let skip = session.entries.len().saturating_sub(RESUME_PREVIEW_ENTRIES);
+    if skip > 0 {
+        println!(
+            "{}\n",
+            style(format!("… {skip} earlier messages not shown")).dim()
+        );
     }
-    let _ = out.flush();
+
+    print_entries(&session.entries[skip..], skin);
 }
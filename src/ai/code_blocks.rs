@@ -0,0 +1,215 @@
+use std::{fmt::Write, fs, path::Path, process::Stdio, time::Duration};
+
+use console::style;
+
+use crate::{
+    session::export::fenced,
+    utils::{
+        commands::copy_to_clipboard, console::get_select_theme, interrupt::Generation,
+        shell::shell_command,
+    },
+};
+
+/// A fenced code block in an answer
+pub struct CodeBlock {
+    pub lang: String,
+    pub code: String,
+}
+
+/// Fenced code blocks in the order they appear
+pub fn extract(markdown: &str) -> Vec<CodeBlock> {
+    let mut blocks = vec![];
+    let mut current: Option<(&str, CodeBlock)> = None;
+
+    for line in markdown.lines() {
+        match current.as_mut() {
+            Some((fence, _)) if is_closing(line, fence) => {
+                blocks.extend(current.take().map(|(_, block)| block));
+            }
+            Some((_, block)) => {
+                block.code.push_str(line);
+                block.code.push('\n');
+            }
+            None => {
+                current = opening(line).map(|(fence, lang)| {
+                    let block = CodeBlock {
+                        lang: lang.to_string(),
+                        code: String::new(),
+                    };
+                    (fence, block)
+                })
+            }
+        }
+    }
+
+    // An unterminated block runs to the end of the answer
+    blocks.extend(current.map(|(_, block)| block));
+    blocks
+}
+
+/// Number of code blocks opened so far and whether the last one is still open
+pub fn scan(markdown: &str) -> (usize, bool) {
+    let mut count = 0;
+    let mut fence = None;
+
+    for line in markdown.lines() {
+        match fence {
+            Some(open) if is_closing(line, open) => fence = None,
+            Some(_) => {}
+            None => {
+                if let Some((open, _)) = opening(line) {
+                    fence = Some(open);
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    (count, fence.is_some())
+}
+
+/// Copy a code block, or the whole answer without a number
+pub fn copy(answer: &str, number: Option<usize>) -> Result<String, String> {
+    let (text, what) = match number {
+        Some(number) => (find(answer, number)?.code, format!("Code block {number}")),
+        None => (answer.to_string(), "The answer".to_string()),
+    };
+
+    copy_to_clipboard(&text).map_err(|e| e.to_string())?;
+    Ok(format!("{what} was copied to the clipboard"))
+}
+
+pub fn save(answer: &str, number: usize, file: &str) -> Result<String, String> {
+    let block = find(answer, number)?;
+    fs::write(Path::new(file), block.code).map_err(|e| format!("Failed to write {file}: {e}"))?;
+    Ok(format!("Code block {number} saved to {file}"))
+}
+
+/// Run a code block in the user's shell after asking for confirmation. Like
+/// the shell tool it gets no input and is killed after `timeout`, and Ctrl-C
+/// stops it. Returns a message with the output when the user wants to send it
+/// back.
+pub async fn run(
+    answer: &str,
+    number: usize,
+    timeout: Duration,
+    output_limit: usize,
+) -> Result<Option<String>, String> {
+    let block = find(answer, number)?;
+
+    println!("{}\n", style(block.code.trim_end()).yellow());
+    if !confirm(&format!("Run code block {number}?")) {
+        return Ok(None);
+    }
+
+    let child = tokio::process::Command::from(shell_command(&block.code))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run code block {number}: {e}"))?;
+
+    let generation = Generation::start();
+    let output = tokio::select! {
+        output = tokio::time::timeout(timeout, child.wait_with_output()) => output,
+        _ = generation.interrupted() => {
+            println!("{} {}\n", style("✗").red(), style("Interrupted").dim());
+            return Ok(None);
+        }
+    };
+    drop(generation);
+
+    let (text, status, success) = match output {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            let status = match output.status.code() {
+                Some(code) => format!("exit code {code}"),
+                None => "terminated by a signal".to_string(),
+            };
+            (text, status, output.status.success())
+        }
+        Ok(Err(e)) => return Err(format!("Failed to run code block {number}: {e}")),
+        Err(_) => {
+            let status = format!("a timeout (killed after {} seconds)", timeout.as_secs());
+            (String::new(), status, false)
+        }
+    };
+
+    if !text.trim().is_empty() {
+        println!("{}", text.trim_end());
+    }
+    let mark = match success {
+        true => style("✔").green(),
+        false => style("✗").red(),
+    };
+    println!(
+        "{mark} {}\n",
+        style(format!("Finished with {status}")).dim()
+    );
+
+    if !confirm("Send the output as your next message?") {
+        return Ok(None);
+    }
+
+    let mut message = format!(
+        "I ran code block {number}:\n\n{}\nIt finished with {status}.",
+        fenced(&block.code, &block.lang)
+    );
+    if !text.trim().is_empty() {
+        let truncated = truncate(&text, output_limit);
+        let _ = write!(message, " Output:\n\n{}", fenced(&truncated, "").trim_end());
+    }
+
+    Ok(Some(message))
+}
+
+fn find(answer: &str, number: usize) -> Result<CodeBlock, String> {
+    let mut blocks = extract(answer);
+    match blocks.len() {
+        0 => Err("The last answer has no code blocks".to_string()),
+        len if number == 0 || number > len => {
+            Err(format!("There is no code block {number} (1-{len})"))
+        }
+        _ => Ok(blocks.swap_remove(number - 1)),
+    }
+}
+
+fn confirm(prompt: &str) -> bool {
+    dialoguer::Select::with_theme(&get_select_theme())
+        .with_prompt(prompt)
+        .default(0)
+        .items(&["Yes", "No"])
+        .clear(true)
+        .interact()
+        .unwrap_or(1)
+        == 0
+}
+
+/// The opening fence and info string of a code block
+pub fn opening(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    ["```", "~~~"]
+        .into_iter()
+        .find(|fence| line.starts_with(fence))
+        .map(|fence| (fence, line.trim_start_matches(fence).trim()))
+}
+
+pub fn is_closing(line: &str, fence: &str) -> bool {
+    let line = line.trim();
+    line.starts_with(fence) && line.trim_start_matches(fence).is_empty()
+}
+
+/// Keep the end of long output, which usually holds the error
+fn truncate(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_string();
+    }
+
+    let mut start = text.len() - limit;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[… {start} bytes cut]\n{}", &text[start..])
+}
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use console::style;
+use indicatif::ProgressBar;
+
+use crate::{
+    client::{ChatError, ErrorKind},
+    config::Config,
+    provider::{llm_models, Provider},
+    session::Session,
+    utils::{console::get_spinner_style, interrupt::Generation},
+};
+
+/// Replace older entries with a summary written by a cheap model. Returns the
+/// number of entries that were compacted, or `None` when there is nothing to
+/// compact. If the summary can't be created, the entries are left untouched.
+pub async fn compact(
+    cfg: &Config,
+    session: &mut Session,
+    provider: &Provider,
+    keep: usize,
+) -> Result<Option<usize>, ChatError> {
+    let indices = session.compactable(keep);
+    if indices.is_empty() {
+        return Ok(None);
+    }
+
+    let mut summarizer = provider.clone();
+    let summary_model = llm_models::summary_model(provider.name());
+    if cfg
+        .available_completion_models()
+        .iter()
+        .any(|(p, id, _)| *p == provider.name() && id == summary_model)
+    {
+        summarizer.set_completion_model(summary_model.to_string());
+    }
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(get_spinner_style());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner.set_message(style("Compacting conversation...").dim().bold().to_string());
+
+    let transcript = session.transcript(&indices);
+    // Ctrl-C cancels the summary like a generation instead of quitting
+    let generation = Generation::start();
+    let summary = tokio::select! {
+        summary = summarizer.summarize(&transcript) => summary,
+        _ = generation.interrupted() => Err(ChatError::new(ErrorKind::Other, "Interrupted")),
+    };
+    drop(generation);
+
+    spinner.finish_and_clear();
+
+    let summary = summary?;
+    if summary.trim().is_empty() {
+        return Err(ChatError::new(
+            ErrorKind::Other,
+            "The model returned an empty summary",
+        ));
+    }
+    session.compact(&indices, summary);
+
+    Ok(Some(indices.len()))
+}
@@ -0,0 +1,279 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use console::{measure_text_width, style, truncate_str, Term};
+use dialoguer::Select;
+use futures::future::join_all;
+use indicatif::ProgressBar;
+use rig::message::Message;
+use termimad::MadSkin;
+
+use crate::{
+    ai::{links, render, utils::provider_for_model},
+    attachments::{
+        media::{self, Attachment},
+        stdin::Piped,
+        FileContext,
+    },
+    client::{ChatError, ChatResponse},
+    config::Config,
+    provider::Provider,
+    tools::BuiltinTools,
+    utils::console::{format_error, get_select_theme, get_spinner_style},
+};
+
+/// Narrower columns are hard to read, so the answers go one after another
+const MIN_COLUMN_WIDTH: usize = 30;
+const COLUMN_SEPARATOR: &str = " │ ";
+
+/// Models that answer the next message in chat
+pub struct Comparison {
+    pub providers: Vec<Provider>,
+    /// Print the answers side by side
+    pub columns: bool,
+}
+
+/// The prompt as one of the compared models gets it
+pub struct Request {
+    provider: Provider,
+    prompt: Message,
+    messages: Vec<Message>,
+}
+
+impl Request {
+    pub fn new(
+        provider: &Provider,
+        text: &str,
+        attachments: &[Attachment],
+        messages: Vec<Message>,
+    ) -> Self {
+        let prompt = media::user_message(
+            text,
+            attachments,
+            &provider.name(),
+            &provider.completion_model(),
+        );
+
+        Self {
+            provider: provider.clone(),
+            prompt,
+            messages,
+        }
+    }
+}
+
+pub struct Answer {
+    pub provider: Provider,
+    pub response: Result<ChatResponse, ChatError>,
+    pub latency: Duration,
+}
+
+impl Answer {
+    pub fn model_id(&self) -> String {
+        self.provider.completion_model()
+    }
+
+    fn title(&self, number: usize) -> String {
+        format!("[{number}] {} ({})", self.model_id(), self.provider.name())
+    }
+
+    fn stats(&self) -> String {
+        let seconds = self.latency.as_secs_f64();
+        match &self.response {
+            Ok(response) => format!(
+                "{seconds:.1}s · {} in / {} out tokens",
+                response.usage.input_tokens, response.usage.output_tokens
+            ),
+            Err(_) => format!("{seconds:.1}s · failed"),
+        }
+    }
+}
+
+/// Models given by ID or display name, separated by commas
+pub fn providers(cfg: &Config, models: &str) -> Result<Vec<Provider>, String> {
+    let providers = models
+        .split(',')
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+        .map(|model| {
+            provider_for_model(cfg, model).ok_or(format!(
+                "Model '{model}' not found. Run `termai model list` to see the available models"
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match providers.len() {
+        0 | 1 => Err("Give at least two models to compare, separated by commas".to_string()),
+        _ => Ok(providers),
+    }
+}
+
+/// Send every request at once. Tools are left out, since confirmation
+/// prompts of several models can't share the terminal.
+pub async fn run(requests: Vec<Request>) -> Vec<Answer> {
+    join_all(requests.into_iter().map(|request| async move {
+        let start = Instant::now();
+        let response = request
+            .provider
+            .chat(
+                request.prompt,
+                request.messages,
+                &mut vec![],
+                &BuiltinTools::default(),
+                &ProgressBar::hidden(),
+                false,
+            )
+            .await;
+
+        Answer {
+            provider: request.provider,
+            response,
+            latency: start.elapsed(),
+        }
+    }))
+    .await
+}
+
+/// Print the answers one after another, or side by side when `columns` is
+/// set and the terminal is wide enough
+pub fn print_answers(skin: &MadSkin, answers: &[Answer], columns: bool) {
+    let width = Term::stdout().size().1 as usize;
+    let count = answers.len().max(1);
+    let column = width.saturating_sub(measure_text_width(COLUMN_SEPARATOR) * (count - 1)) / count;
+
+    if columns && column >= MIN_COLUMN_WIDTH {
+        print_columns(skin, answers, column);
+        return;
+    }
+
+    for (i, answer) in answers.iter().enumerate() {
+        println!("{}", style(answer.title(i + 1)).bold());
+        println!("{}\n", style(answer.stats()).dim());
+        match &answer.response {
+            Ok(response) => render::print_answer(skin, &response.text),
+            Err(e) => println!("{}", format_error(e)),
+        }
+        println!();
+    }
+}
+
+fn print_columns(skin: &MadSkin, answers: &[Answer], width: usize) {
+    let cells = answers
+        .iter()
+        .enumerate()
+        .map(|(i, answer)| {
+            let body = match &answer.response {
+                Ok(response) => render::render_answer(skin, &response.text, width),
+                Err(e) => format_error(e),
+            };
+
+            let mut lines = vec![
+                style(answer.title(i + 1)).bold().to_string(),
+                style(answer.stats()).dim().to_string(),
+                String::new(),
+            ];
+            // Links are dropped, since cutting off a line could break them
+            lines.extend(body.lines().map(|line| links::strip(line).into_owned()));
+            lines
+        })
+        .collect::<Vec<_>>();
+
+    let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+    let separator = style(COLUMN_SEPARATOR).dim().to_string();
+
+    for row in 0..height {
+        let line = cells
+            .iter()
+            .map(|lines| {
+                let line = lines.get(row).map(String::as_str).unwrap_or_default();
+                // Code isn't wrapped, so long lines are cut off
+                let line = truncate_str(line, width, "…");
+                let padding = width.saturating_sub(measure_text_width(&line));
+                format!("{line}{}", " ".repeat(padding))
+            })
+            .collect::<Vec<_>>()
+            .join(&separator);
+        println!("{}", line.trim_end());
+    }
+    println!();
+}
+
+/// Ask which answer goes into the conversation. `None` keeps none of them.
+pub fn choose(answers: &[Answer]) -> Option<usize> {
+    let choices = answers
+        .iter()
+        .enumerate()
+        .filter(|(_, answer)| answer.response.is_ok())
+        .collect::<Vec<_>>();
+    let items = choices
+        .iter()
+        .map(|(i, answer)| answer.title(i + 1))
+        .collect::<Vec<_>>();
+
+    if items.is_empty() {
+        return None;
+    }
+
+    let selection = Select::with_theme(&get_select_theme())
+        .with_prompt("Keep which answer? (Esc keeps none)")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .ok()
+        .flatten()?;
+
+    choices.get(selection).map(|(i, _)| *i)
+}
+
+/// `termai compare`: send one prompt to several models and print the answers
+pub async fn compare(
+    cfg: &Config,
+    models: &str,
+    prompt: &str,
+    columns: bool,
+    files: &[PathBuf],
+    stdin: Option<&Piped>,
+) -> Result<(), String> {
+    let providers = providers(cfg, models)?;
+
+    let mut context = FileContext::collect(files, true);
+    if let Some(piped) = stdin {
+        context.add_piped(piped);
+    }
+    for (path, reason) in &context.skipped {
+        eprintln!("{} Skipped {} ({reason})", style("✗").red(), path.display());
+    }
+    if let Some(note) = context.omitted_note() {
+        eprintln!("{} {note}", style("✗").red());
+    }
+    for provider in &providers {
+        media::check_supported(
+            &context.media,
+            &provider.name(),
+            &provider.completion_model(),
+        )?;
+    }
+
+    let text = context.apply(prompt);
+    let requests = providers
+        .iter()
+        .map(|provider| Request::new(provider, &text, &context.media, vec![]))
+        .collect();
+
+    let spinner = ProgressBar::new_spinner();
+    spinner.set_style(get_spinner_style());
+    spinner.set_message(format!("Asking {} models", providers.len()));
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    let answers = run(requests).await;
+    spinner.finish_and_clear();
+
+    println!();
+    print_answers(&render::skin(), &answers, columns);
+
+    match answers.iter().any(|answer| answer.response.is_ok()) {
+        true => Ok(()),
+        false => Err("None of the models answered".to_string()),
+    }
+}
@@ -1,15 +1,23 @@
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use console::style;
 use indicatif::ProgressBar;
 
 use crate::{
     ai::utils::{on_the_fly_change_model, NO_MODELS_FOUND_MSG},
+    attachments::{stdin::Piped, FileContext},
     config::Config,
-    utils::console::get_spinner_style,
+    editor::Editor,
+    utils::console::{format_error, get_spinner_style},
 };
 
-pub async fn explain(cfg: &Config, query: Option<String>, select_model: bool) {
+pub async fn explain(
+    cfg: &Config,
+    query: Option<String>,
+    select_model: bool,
+    files: Vec<PathBuf>,
+    stdin: Option<Piped>,
+) {
     let mut provider = cfg
         .active_provider()
         .unwrap_or_else(|| {
@@ -33,11 +41,8 @@ pub async fn explain(cfg: &Config, query: Option<String>, select_model: bool) {
     let query = query.unwrap_or_else(|| {
         println!();
 
-        let prompt = format!{"{} {}", style("?").green().bold(), style("What shell command would you like explained?\n").bold()};
-        dialoguer::Input::<String>::new()
-            .with_prompt(prompt)
-            .allow_empty(false)
-            .interact().unwrap_or_default()
+        let prompt = format!{"{} {}", style("?").green().bold(), style("What shell command would you like explained?").bold()};
+        Editor::ask(&prompt, "explain")
     });
 
     if query.trim().is_empty() {
@@ -46,14 +51,30 @@ pub async fn explain(cfg: &Config, query: Option<String>, select_model: bool) {
 
     println!();
 
+    let query = if files.is_empty() && stdin.is_none() {
+        query
+    } else {
+        let mut context = FileContext::collect(&files, false);
+        if let Some(piped) = &stdin {
+            context.add_piped(piped);
+        }
+        context.print_summary();
+        println!();
+        context.apply(&query)
+    };
+
     let spinner = ProgressBar::new_spinner();
     spinner.set_style(get_spinner_style());
     spinner.enable_steady_tick(Duration::from_millis(100));
     spinner.set_message(style("Thinking...").dim().bold().to_string());
 
     let explanation = match provider.explain(&query).await {
-        Ok(result) => result.replace(r"\x1b", "\x1b"), // Fix ANSI escape codes
-        Err(e) => e.to_string(),
+        Ok(result) => result.text.replace(r"\x1b", "\x1b"), // Fix ANSI escape codes
+        Err(e) => {
+            spinner.finish_and_clear();
+            eprintln!("{}\n", format_error(&e));
+            return;
+        }
     };
 
     spinner.finish_and_clear();
@@ -0,0 +1,129 @@
+use std::{str::FromStr, sync::LazyLock};
+
+use console::{colors_enabled, Style};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Color, FontStyle, ScopeSelectors, StyleModifier, Theme, ThemeItem},
+    parsing::{SyntaxReference, SyntaxSet},
+};
+
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME: LazyLock<Theme> = LazyLock::new(ansi_theme);
+
+/// Colors are indexes into the terminal's own 16-color palette, so the
+/// terminal picks shades that are readable on its background
+const SCOPES: &[(&str, u8, FontStyle)] = &[
+    ("comment", 8, FontStyle::ITALIC),
+    ("string, constant.character", 2, FontStyle::empty()),
+    ("constant.numeric, constant.language", 3, FontStyle::empty()),
+    ("keyword, storage, variable.language", 5, FontStyle::empty()),
+    (
+        "entity.name.function, support.function, variable.function",
+        4,
+        FontStyle::empty(),
+    ),
+    (
+        "entity.name.type, entity.name.class, entity.name.struct, entity.name.enum, support.type, support.class",
+        6,
+        FontStyle::empty(),
+    ),
+    ("entity.name.tag, entity.other.attribute-name", 4, FontStyle::empty()),
+    ("markup.heading", 4, FontStyle::BOLD),
+    ("markup.inserted", 2, FontStyle::empty()),
+    ("markup.deleted", 1, FontStyle::empty()),
+];
+
+/// Highlight the code of a fenced block. Unknown languages and terminals
+/// without colors (e.g. with `NO_COLOR` set) get the code as it is.
+pub fn code(lang: &str, code: &str) -> String {
+    let code = code.replace('\t', "    ");
+    match syntax(lang).filter(|_| colors_enabled()) {
+        Some(syntax) => highlight(syntax, &code),
+        None => code,
+    }
+}
+
+fn syntax(lang: &str) -> Option<&'static SyntaxReference> {
+    // The info string may hold more than the language, e.g. `rust,ignore`
+    let token = lang.split([' ', ',', '{']).next().unwrap_or_default();
+    if token.is_empty() {
+        return None;
+    }
+
+    let token = match token.to_lowercase().as_str() {
+        "shell" | "console" | "zsh" => "bash".to_string(),
+        "py" => "python".to_string(),
+        token => token.to_string(),
+    };
+    SYNTAXES
+        .find_syntax_by_token(&token)
+        .filter(|syntax| syntax.name != "Plain Text")
+}
+
+fn highlight(syntax: &SyntaxReference, code: &str) -> String {
+    let mut highlighter = HighlightLines::new(syntax, &THEME);
+    let mut output = String::with_capacity(code.len() * 2);
+
+    for line in code.split_inclusive('\n') {
+        let Ok(regions) = highlighter.highlight_line(line, &SYNTAXES) else {
+            // Show the line as it is if the grammar fails on it
+            output.push_str(line);
+            continue;
+        };
+
+        for (region, text) in regions {
+            let text = text.trim_end_matches('\n');
+            if text.is_empty() {
+                continue;
+            }
+            output.push_str(&style(region).apply_to(text).to_string());
+        }
+        if line.ends_with('\n') {
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+fn style(region: syntect::highlighting::Style) -> Style {
+    let mut style = Style::new();
+    // Alpha 0 marks a palette index, anything else is the terminal's default
+    if region.foreground.a == 0 {
+        style = style.color256(region.foreground.r);
+    }
+    if region.font_style.contains(FontStyle::BOLD) {
+        style = style.bold();
+    }
+    if region.font_style.contains(FontStyle::ITALIC) {
+        style = style.italic();
+    }
+    style
+}
+
+fn ansi_theme() -> Theme {
+    let scopes = SCOPES
+        .iter()
+        .filter_map(|(selector, color, font_style)| {
+            let foreground = Color {
+                r: *color,
+                g: 0,
+                b: 0,
+                a: 0,
+            };
+            Some(ThemeItem {
+                scope: ScopeSelectors::from_str(selector).ok()?,
+                style: StyleModifier {
+                    foreground: Some(foreground),
+                    background: None,
+                    font_style: Some(*font_style),
+                },
+            })
+        })
+        .collect();
+
+    Theme {
+        scopes,
+        ..Default::default()
+    }
+}
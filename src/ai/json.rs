@@ -0,0 +1,253 @@
+use std::path::PathBuf;
+
+use futures::StreamExt;
+use indicatif::ProgressBar;
+use serde::Serialize;
+
+use crate::{
+    ai::ask::OneShot,
+    attachments::stdin::Piped,
+    client::{
+        ChatError, ChatResponse, Citation, ErrorKind, StopReason, StreamingContent, ToolCallRecord,
+        Usage,
+    },
+    config::Config,
+    session::Session,
+    tools::BuiltinTools,
+};
+
+pub struct JsonRequest {
+    /// `chat`, `ask`, `suggest` or `explain`
+    pub command: &'static str,
+    pub prompt: String,
+    pub search: bool,
+    pub incognito: bool,
+    pub files: Vec<PathBuf>,
+    pub stdin: Option<Piped>,
+}
+
+#[derive(Serialize)]
+struct Output<'a> {
+    content: &'a str,
+    model: &'a str,
+    provider: String,
+    stop_reason: StopReason,
+    usage: Usage,
+    tool_calls: &'a [ToolCallRecord],
+    /// Sources of a search answer
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    citations: &'a [Citation],
+}
+
+/// Events printed as one JSON object per line while streaming
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event<'a> {
+    Text {
+        text: &'a str,
+    },
+    ToolCall(&'a ToolCallRecord),
+    Done {
+        model: &'a str,
+        provider: String,
+        stop_reason: StopReason,
+        usage: Usage,
+        #[serde(skip_serializing_if = "<[_]>::is_empty")]
+        citations: &'a [Citation],
+    },
+    Error {
+        message: &'a str,
+        kind: ErrorKind,
+        hint: Option<&'a str>,
+    },
+}
+
+/// Answer a single prompt with a JSON object on stdout (NDJSON events when a
+/// chat is streamed). Returns the exit code.
+pub async fn json(cfg: &mut Config, request: JsonRequest) -> i32 {
+    let JsonRequest {
+        command,
+        prompt,
+        search,
+        incognito,
+        files,
+        stdin,
+    } = request;
+
+    if prompt.trim().is_empty() && stdin.is_none() {
+        print_error("Please provide a prompt or pipe something into termai");
+        return 2;
+    }
+
+    let with_media = matches!(command, "chat" | "ask");
+    let one_shot = match OneShot::new(cfg, &prompt, search, &files, stdin.as_ref(), with_media) {
+        Ok(one_shot) => one_shot,
+        Err(e) => return print_error(&e),
+    };
+    let provider = &one_shot.provider;
+
+    let result = match command {
+        "suggest" => provider.suggest(&one_shot.text).await,
+        "explain" => provider.explain(&one_shot.text).await,
+        // Tools are left out since their confirmation prompts need a terminal
+        "ask" => {
+            provider
+                .chat(
+                    one_shot.message(),
+                    vec![],
+                    &mut vec![],
+                    &BuiltinTools::default(),
+                    &ProgressBar::hidden(),
+                    search,
+                )
+                .await
+        }
+        _ if cfg.streaming() => {
+            let (response, code) = match stream(cfg, &one_shot, search).await {
+                Ok(response) => (response, 0),
+                Err(response) => (response, 1),
+            };
+            if !response.text.is_empty() {
+                save_session(&one_shot, &response, incognito);
+            }
+            return code;
+        }
+        _ => {
+            let tools = cfg.builtin_tools();
+            let response = provider
+                .chat(
+                    one_shot.message(),
+                    vec![],
+                    cfg.mcp_clients(),
+                    &tools,
+                    &ProgressBar::hidden(),
+                    search,
+                )
+                .await;
+            if let Ok(response) = &response {
+                save_session(&one_shot, response, incognito);
+            }
+            response
+        }
+    };
+
+    match result {
+        Ok(response) => {
+            print_line(&Output {
+                content: response.text.trim(),
+                model: &response.model,
+                provider: provider_id(&one_shot),
+                stop_reason: response.stop_reason,
+                usage: response.usage,
+                tool_calls: &response.tool_calls,
+                citations: &response.citations,
+            });
+            0
+        }
+        Err(e) => print_chat_error(&e),
+    }
+}
+
+/// Stream a chat answer as NDJSON events and collect it for the session. When
+/// the stream fails, what arrived before the error is returned as the error.
+async fn stream(
+    cfg: &mut Config,
+    one_shot: &OneShot,
+    search: bool,
+) -> Result<ChatResponse, ChatResponse> {
+    let mut response = ChatResponse {
+        model: one_shot.model_id.to_owned(),
+        ..Default::default()
+    };
+
+    let tools = cfg.builtin_tools();
+    let mut stream = one_shot
+        .provider
+        .chat_stream(
+            one_shot.message(),
+            vec![],
+            cfg.mcp_clients(),
+            &tools,
+            search,
+        )
+        .await;
+
+    while let Some(content) = stream.next().await {
+        match content {
+            Ok(StreamingContent::Text(text)) => {
+                print_line(&Event::Text { text: &text });
+                response.text.push_str(&text);
+            }
+            Ok(StreamingContent::ToolCall(tool_call)) => {
+                print_line(&Event::ToolCall(&tool_call));
+                response.tool_calls.push(tool_call);
+            }
+            Ok(StreamingContent::Citations(citations)) => {
+                response.citations = citations;
+            }
+            Ok(StreamingContent::Done(stop_reason, usage)) => {
+                response.stop_reason = stop_reason;
+                response.usage = usage;
+            }
+            Ok(StreamingContent::PauseSpinner | StreamingContent::StartSpinner) => {}
+            Err(e) => {
+                print_line(&Event::Error {
+                    message: &e.message,
+                    kind: e.kind,
+                    hint: e.hint(),
+                });
+                return Err(response);
+            }
+        }
+    }
+
+    print_line(&Event::Done {
+        model: &response.model,
+        provider: provider_id(one_shot),
+        stop_reason: response.stop_reason,
+        usage: response.usage,
+        citations: &response.citations,
+    });
+
+    Ok(response)
+}
+
+fn print_error(message: &str) -> i32 {
+    println!("{}", serde_json::json!({ "error": message }));
+    1
+}
+
+/// Failed requests also tell scripts what kind of failure it was
+fn print_chat_error(error: &ChatError) -> i32 {
+    println!(
+        "{}",
+        serde_json::json!({ "error": error.message, "kind": error.kind, "hint": error.hint() })
+    );
+    1
+}
+
+fn print_line<T: Serialize>(value: &T) {
+    println!(
+        "{}",
+        serde_json::to_string(value).expect("Output is always serializable")
+    );
+}
+
+fn provider_id(one_shot: &OneShot) -> String {
+    one_shot.provider.name().to_string().to_lowercase()
+}
+
+/// One-shot chats are saved like interactive ones unless incognito
+fn save_session(one_shot: &OneShot, response: &ChatResponse, incognito: bool) {
+    let mut session = Session::new(&one_shot.model_id, incognito);
+    session.push_user(&one_shot.text, one_shot.attachments.to_owned());
+    session.push_assistant(
+        &response.text,
+        &one_shot.model_id,
+        response.tool_calls.to_owned(),
+    );
+
+    if let Err(e) = session.save() {
+        eprintln!("{} {e}", console::style("✗").red());
+    }
+}
@@ -0,0 +1,173 @@
+use std::{env, sync::LazyLock};
+
+use console::Term;
+use regex::{Captures, Regex};
+
+/// Zero-width markers put around each word of a link before the markdown is
+/// rendered, so wrapping sees the link text only. They are replaced with
+/// OSC 8 sequences afterwards.
+const START: char = '\u{2061}';
+const MORE: char = '\u{2062}';
+const END: char = '\u{2063}';
+
+/// Variables set by terminals that support hyperlinks
+const TERMINAL_VARS: &[&str] = &[
+    "WT_SESSION",
+    "KITTY_WINDOW_ID",
+    "WEZTERM_EXECUTABLE",
+    "KONSOLE_VERSION",
+    "DOMTERM",
+];
+const TERM_PROGRAMS: &[&str] = &[
+    "iTerm.app",
+    "WezTerm",
+    "vscode",
+    "ghostty",
+    "Hyper",
+    "Tabby",
+    "rio",
+];
+const TERMS: &[&str] = &[
+    "xterm-kitty",
+    "alacritty",
+    "foot",
+    "xterm-ghostty",
+    "wezterm",
+];
+
+static LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"!?\[([^\]\n]+)\]\(<?([^)\s>]+)>?(?:\s+"[^"\n]*")?\)|<(https?://[^>\s]+)>"#)
+        .expect("Valid link regex")
+});
+static HYPERLINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1B\]8;[^\x07\x1B]*;[^\x07\x1B]*(\x07|\x1B\\)").expect("Valid hyperlink regex")
+});
+
+/// Prepare the links in `markdown`. Terminals that support hyperlinks get
+/// marked link text and the URLs for `apply`, others get `text (url)`.
+pub fn mark(markdown: &str) -> (String, Vec<String>) {
+    let enabled = supported();
+    let mut urls = vec![];
+
+    let marked = outside_code(markdown, |text| {
+        LINK.replace_all(text, |caps: &Captures| {
+            let (text, url) = match (caps.get(1), caps.get(2), caps.get(3)) {
+                (Some(text), Some(url), _) => (text.as_str(), url.as_str()),
+                (_, _, Some(url)) => (url.as_str(), url.as_str()),
+                _ => return caps[0].to_string(),
+            };
+
+            if !enabled {
+                return match text == url {
+                    true => url.to_string(),
+                    false => format!("{text} ({url})"),
+                };
+            }
+
+            urls.push(url.to_string());
+            text.split(' ')
+                .enumerate()
+                .map(|(i, word)| match (i, word.is_empty()) {
+                    (_, true) => String::new(),
+                    (0, false) => format!("{START}{word}{END}"),
+                    _ => format!("{MORE}{word}{END}"),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .into_owned()
+    });
+
+    (marked, urls)
+}
+
+/// Replace the markers in rendered text with hyperlinks to `urls`
+pub fn apply(rendered: &str, urls: &[String]) -> String {
+    if urls.is_empty() {
+        return rendered.to_string();
+    }
+
+    let mut output = String::with_capacity(rendered.len());
+    let mut current = None;
+    let mut next = 0;
+
+    for c in rendered.chars() {
+        match c {
+            START => {
+                current = urls.get(next);
+                next += 1;
+                output.push_str(&open(current));
+            }
+            MORE => output.push_str(&open(current)),
+            END => output.push_str("\x1B]8;;\x1B\\"),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Text without hyperlink sequences, which don't take up any space
+pub fn strip(line: &str) -> std::borrow::Cow<'_, str> {
+    HYPERLINK.replace_all(line, "")
+}
+
+fn open(url: Option<&String>) -> String {
+    format!(
+        "\x1B]8;;{}\x1B\\",
+        url.map(String::as_str).unwrap_or_default()
+    )
+}
+
+/// Whether the terminal shows OSC 8 hyperlinks. There is no way to ask, so
+/// this goes by the terminals known to support them. `FORCE_HYPERLINK=1`
+/// or `0` overrides it.
+fn supported() -> bool {
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    if !Term::stdout().is_term() {
+        return false;
+    }
+
+    let var = |name: &str| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+
+    TERMINAL_VARS.iter().any(|name| env::var_os(name).is_some())
+        || var("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000)
+        || TERM_PROGRAMS.contains(&var("TERM_PROGRAM").as_str())
+        || TERMS.iter().any(|name| term.starts_with(name))
+}
+
+/// Apply `f` to the parts of `markdown` outside of inline code spans
+fn outside_code(markdown: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find('`') {
+        let ticks = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+        let after = start + ticks;
+        let fence = &rest[start..after];
+
+        // A span ends at the next run of exactly as many backticks
+        let end = rest[after..]
+            .match_indices(fence)
+            .find(|(i, _)| !rest[after + i + ticks..].starts_with('`'))
+            .map(|(i, _)| after + i + ticks);
+
+        output.push_str(&f(&rest[..start]));
+        match end {
+            Some(end) => {
+                output.push_str(&rest[start..end]);
+                rest = &rest[end..];
+            }
+            None => {
+                output.push_str(fence);
+                rest = &rest[after..];
+            }
+        }
+    }
+
+    output.push_str(&f(rest));
+    output
+}
@@ -1,14 +1,44 @@
+mod ask;
 mod chat;
+mod code_blocks;
+mod compact;
+mod compare;
 mod explain;
+mod highlight;
+mod json;
+mod links;
+mod render;
+mod sources;
+mod status;
 mod suggest;
+mod table;
 mod utils;
 
+use std::path::PathBuf;
+
+use ask::ask;
 use chat::chat;
 use console::Term;
 use explain::explain;
+use json::json;
 use suggest::suggest;
 
-use crate::config::Config;
+use crate::{attachments::stdin::Piped, client::ChatResponse, config::Config};
+
+pub use json::JsonRequest;
+
+pub struct ChatOptions {
+    pub initial_message: Option<String>,
+    pub select_model: bool,
+    pub search: bool,
+    /// `Some(None)` resumes the most recent session
+    pub resume: Option<Option<String>>,
+    pub incognito: bool,
+    /// Files and directories attached to the first prompt
+    pub files: Vec<PathBuf>,
+    /// Text piped into termai, attached to the first prompt
+    pub stdin: Option<Piped>,
+}
 
 pub struct AI<'a> {
     term: &'a Term,
@@ -20,27 +50,53 @@ impl AI<'_> {
         AI::<'a> { term, cfg }
     }
 
-    pub async fn chat(
-        &mut self,
-        initial_message: Option<String>,
-        select_model: bool,
+    pub async fn chat(&mut self, options: ChatOptions) {
+        chat(self.term, self.cfg, options).await;
+    }
+
+    pub async fn ask(
+        &self,
+        prompt: String,
         search: bool,
-    ) {
-        chat(
-            self.term,
-            self.cfg,
-            initial_message,
-            select_model,
-            Some(search),
-        )
-        .await;
+        files: Vec<PathBuf>,
+        stdin: Option<Piped>,
+    ) -> Result<ChatResponse, String> {
+        ask(self.cfg, prompt, search, files, stdin).await
     }
 
-    pub async fn explain(&self, query: Option<String>, select_model: bool) {
-        explain(self.cfg, query, select_model).await;
+    pub async fn compare(
+        &self,
+        models: &str,
+        prompt: &str,
+        columns: bool,
+        files: &[PathBuf],
+        stdin: Option<&Piped>,
+    ) -> Result<(), String> {
+        compare::compare(self.cfg, models, prompt, columns, files, stdin).await
     }
 
-    pub async fn suggest(&self, query: Option<String>, select_model: bool) {
-        suggest(self.cfg, query, select_model).await;
+    /// Returns the exit code
+    pub async fn json(&mut self, request: JsonRequest) -> i32 {
+        json(self.cfg, request).await
+    }
+
+    pub async fn explain(
+        &self,
+        query: Option<String>,
+        select_model: bool,
+        files: Vec<PathBuf>,
+        stdin: Option<Piped>,
+    ) {
+        explain(self.cfg, query, select_model, files, stdin).await;
+    }
+
+    pub async fn suggest(
+        &self,
+        query: Option<String>,
+        select_model: bool,
+        files: Vec<PathBuf>,
+        stdin: Option<Piped>,
+    ) {
+        suggest(self.cfg, query, select_model, files, stdin).await;
     }
 }
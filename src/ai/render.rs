@@ -0,0 +1,315 @@
+use std::io::{stdout, Write};
+
+use console::{colors_enabled, measure_text_width, style, Term};
+use termimad::MadSkin;
+
+use crate::ai::{code_blocks, highlight, links, table::Table};
+
+/// Renders a streamed answer. Finished blocks are printed once and never
+/// touched again, only the block that is still being written is redrawn.
+pub struct StreamRenderer<'a> {
+    skin: &'a MadSkin,
+    term: &'a Term,
+    /// Code blocks in the printed part of the answer, for numbering
+    blocks: usize,
+    /// The trailing block that is still open
+    pending: String,
+    /// `pending` continues a code block whose opening fence is printed already
+    continued: bool,
+    /// Lines of `pending` as they are on screen
+    drawn: Vec<String>,
+}
+
+impl<'a> StreamRenderer<'a> {
+    pub fn new(skin: &'a MadSkin, term: &'a Term) -> Self {
+        Self {
+            skin,
+            term,
+            blocks: 0,
+            pending: String::new(),
+            continued: false,
+            drawn: vec![],
+        }
+    }
+
+    pub fn push(&mut self, text: &str) {
+        self.pending.push_str(text);
+
+        let mut output = self.erase();
+
+        let end = complete_blocks(&self.pending);
+        if end > 0 {
+            let done = self.pending.drain(..end).collect::<String>();
+            output.push_str(&self.render(&done));
+            self.commit(&done);
+        }
+
+        // A block taller than the screen can't be erased, so finished lines
+        // of a long code block are printed for good
+        let mut trailing = self.render(&self.pending);
+        if rows(&trailing, self.width()) + 1 >= self.term.size().0 as usize {
+            let open = open_code_block(&self.pending)
+                .map(|(opening, fence, end)| (opening.to_string(), fence.to_string(), end));
+            if let Some((opening, fence, end)) = open {
+                let done = self.pending.drain(..end).collect::<String>();
+                output.push_str(&self.render(&format!("{done}{fence}\n")));
+                self.commit(&done);
+                self.pending.insert_str(0, &format!("{opening}\n"));
+                self.continued = true;
+                trailing = self.render(&self.pending);
+            }
+        }
+
+        output.push_str(&trailing);
+        self.drawn = trailing.lines().map(str::to_string).collect();
+        print(&output);
+    }
+
+    /// Print the open block for good, e.g. before a tool confirmation prompt
+    /// or at the end of the answer
+    pub fn finish(&mut self) {
+        let mut output = self.erase();
+        let pending = std::mem::take(&mut self.pending);
+        output.push_str(&self.render(&pending));
+        self.commit(&pending);
+        print(&output);
+    }
+
+    fn commit(&mut self, markdown: &str) {
+        let (count, open) = code_blocks::scan(markdown);
+        self.blocks += count - usize::from(self.continued && count > 0);
+        self.continued = self.continued && open;
+    }
+
+    fn render(&self, markdown: &str) -> String {
+        if markdown.is_empty() {
+            return String::new();
+        }
+
+        render_markdown(
+            self.skin,
+            markdown,
+            self.width(),
+            self.blocks,
+            self.continued,
+        )
+    }
+
+    /// Move the cursor back to where the open block starts. Rows are counted
+    /// at the current width, so lines that were reflowed by a resize are
+    /// erased too.
+    fn erase(&mut self) -> String {
+        let width = self.width();
+        let rows = self
+            .drawn
+            .drain(..)
+            .map(|line| line_rows(&line, width))
+            .sum::<usize>();
+
+        match rows {
+            0 => String::new(),
+            rows => format!("\r\x1B[{rows}A\x1B[J"),
+        }
+    }
+
+    fn width(&self) -> usize {
+        (self.term.size().1 as usize).max(1)
+    }
+}
+
+/// Skin for answers, without styles when the terminal has no colors
+pub fn skin() -> MadSkin {
+    match colors_enabled() {
+        true => MadSkin::default(),
+        false => MadSkin::no_style(),
+    }
+}
+
+/// Print a whole answer at once
+pub fn print_answer(skin: &MadSkin, answer: &str) {
+    let width = (Term::stdout().size().1 as usize).max(1);
+    print(&render_answer(skin, answer, width));
+}
+
+/// Render a whole answer for `width` columns
+pub fn render_answer(skin: &MadSkin, answer: &str, width: usize) -> String {
+    render_markdown(skin, answer, width, 0, false)
+}
+
+/// Render markdown with numbered and highlighted code blocks. `first` is the
+/// number of code blocks before `markdown`, and `continued` means it starts
+/// inside a block whose label is printed already.
+fn render_markdown(
+    skin: &MadSkin,
+    markdown: &str,
+    width: usize,
+    first: usize,
+    continued: bool,
+) -> String {
+    let mut output = String::new();
+    let mut text = String::new();
+    let mut count = first;
+    let mut block = None;
+
+    let mut lines = markdown.split_inclusive('\n');
+    if continued {
+        let opening = lines.next().and_then(code_blocks::opening);
+        block = opening.map(|(fence, lang)| (fence, lang, String::new()));
+    }
+
+    for line in lines {
+        match block.as_mut() {
+            Some((fence, lang, code)) if code_blocks::is_closing(line, fence) => {
+                output.push_str(&code_block(lang, code));
+                block = None;
+            }
+            Some((_, _, code)) => code.push_str(line),
+            None => match code_blocks::opening(line) {
+                Some((fence, lang)) => {
+                    output.push_str(&render_text(skin, &std::mem::take(&mut text), width));
+                    count += 1;
+                    let label = format!("[{count}] {lang}");
+                    output.push_str(&format!("{}\n", style(label.trim_end()).italic()));
+                    block = Some((fence, lang, String::new()));
+                }
+                None => text.push_str(line),
+            },
+        }
+    }
+
+    // An unterminated block runs to the end
+    if let Some((_, lang, code)) = block {
+        output.push_str(&code_block(lang, &code));
+    }
+    output.push_str(&render_text(skin, &text, width));
+    output
+}
+
+/// Render markdown outside of code blocks. Tables are drawn here, the rest
+/// goes through the skin.
+fn render_text(skin: &MadSkin, markdown: &str, width: usize) -> String {
+    if markdown.is_empty() {
+        return String::new();
+    }
+
+    let (markdown, urls) = links::mark(markdown);
+    let lines = markdown.split_inclusive('\n').collect::<Vec<_>>();
+    let mut output = String::new();
+    let mut text = String::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        match Table::parse(&lines[i..]) {
+            Some((table, len)) => {
+                if !text.is_empty() {
+                    output.push_str(
+                        &skin
+                            .text(&std::mem::take(&mut text), Some(width))
+                            .to_string(),
+                    );
+                }
+                output.push_str(&table.render(skin, width));
+                i += len;
+            }
+            None => {
+                text.push_str(lines[i]);
+                i += 1;
+            }
+        }
+    }
+    if !text.is_empty() {
+        output.push_str(&skin.text(&text, Some(width)).to_string());
+    }
+
+    links::apply(&output, &urls)
+}
+
+/// Code is indented instead of put on a background, so the block reads well
+/// on dark and light terminals
+fn code_block(lang: &str, code: &str) -> String {
+    highlight::code(lang, code)
+        .lines()
+        .map(|line| format!("  {line}\n"))
+        .collect()
+}
+
+/// End of the last finished block: a blank line outside of a code block, or
+/// the closing fence of a code block. Only complete lines are considered, so
+/// a fence split across chunks is seen once it is whole.
+fn complete_blocks(markdown: &str) -> usize {
+    let mut end = 0;
+    let mut position = 0;
+    let mut fence = None;
+    let mut has_content = false;
+
+    for line in markdown.split_inclusive('\n') {
+        position += line.len();
+        if !line.ends_with('\n') {
+            break;
+        }
+
+        match fence {
+            Some(open) if code_blocks::is_closing(line, open) => {
+                fence = None;
+                end = position;
+                has_content = false;
+            }
+            Some(_) => {}
+            None if line.trim().is_empty() => {
+                if has_content {
+                    end = position;
+                    has_content = false;
+                }
+            }
+            None => {
+                fence = code_blocks::opening(line).map(|(open, _)| open);
+                has_content = true;
+            }
+        }
+    }
+
+    end
+}
+
+/// The opening line and fence of a code block that is still open at the end
+/// of `markdown`, and the end of its last complete line. `None` until the
+/// block has a complete line of code.
+fn open_code_block(markdown: &str) -> Option<(&str, &str, usize)> {
+    let mut open = None;
+    let mut position = 0;
+    let mut end = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        if !line.ends_with('\n') {
+            break;
+        }
+        position += line.len();
+
+        open = match open {
+            Some((_, fence, _)) if code_blocks::is_closing(line, fence) => None,
+            Some(open) => Some(open),
+            None => code_blocks::opening(line).map(|(fence, _)| (line.trim(), fence, position)),
+        };
+        end = position;
+    }
+
+    open.filter(|(_, _, start)| end > *start)
+        .map(|(opening, fence, _)| (opening, fence, end))
+}
+
+fn rows(rendered: &str, width: usize) -> usize {
+    rendered.lines().map(|line| line_rows(line, width)).sum()
+}
+
+/// Rows a line takes up once the terminal wraps it
+fn line_rows(line: &str, width: usize) -> usize {
+    measure_text_width(&links::strip(line))
+        .div_ceil(width)
+        .max(1)
+}
+
+fn print(output: &str) {
+    let mut out = stdout();
+    let _ = out.write_all(output.as_bytes());
+    let _ = out.flush();
+}
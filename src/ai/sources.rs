@@ -0,0 +1,104 @@
+use console::style;
+
+use crate::client::Citation;
+
+/// A web page cited by a search answer, numbered by its first citation
+#[derive(Debug, Clone)]
+pub struct Source {
+    pub title: String,
+    pub url: String,
+}
+
+/// Put the number of its source after each cited range of `text`, like
+/// `[1]`, and list the sources in that order. Ranges that hold a markdown
+/// link to the source are replaced by the number.
+pub fn cite(text: &str, citations: &[Citation]) -> (String, Vec<Source>) {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut citations = citations.iter().collect::<Vec<_>>();
+    citations.sort_by_key(|citation| (citation.start, citation.end));
+
+    let mut sources: Vec<Source> = vec![];
+    let mut cited = String::new();
+    let mut cursor = 0;
+
+    for citation in citations {
+        if citation.start >= citation.end || citation.end > chars.len() {
+            continue;
+        }
+
+        let number = match sources.iter().position(|s| s.url == citation.url) {
+            Some(i) => i + 1,
+            None => {
+                sources.push(Source {
+                    title: citation.title.trim().to_string(),
+                    url: citation.url.clone(),
+                });
+                sources.len()
+            }
+        };
+        let marker = format!("[{number}]");
+
+        let (mut start, mut end) = (citation.start, citation.end);
+        // Several sources for the same range
+        if start < cursor {
+            if !cited.ends_with(&marker) {
+                cited.push_str(&marker);
+            }
+            continue;
+        }
+
+        let range = chars[start..end].iter().collect::<String>();
+        let link = range.contains("](");
+        if link && start > 0 && chars[start - 1] == '(' && chars.get(end) == Some(&')') {
+            start -= 1;
+            end += 1;
+        }
+
+        cited.extend(&chars[cursor..start]);
+        if !link {
+            cited.push_str(&range);
+        }
+        cited.push_str(&marker);
+        cursor = end;
+    }
+    cited.extend(&chars[cursor..]);
+
+    (cited, sources)
+}
+
+/// Numbered titles and URLs, matching the markers of the answer
+pub fn print_sources(sources: &[Source]) {
+    println!("{}", style("Sources:").bold().underlined());
+    for (i, source) in sources.iter().enumerate() {
+        let number = format!("[{}]", i + 1);
+        let title = match source.title.is_empty() {
+            true => &source.url,
+            false => &source.title,
+        };
+        println!("  {} {title}", style(&number).bold());
+        println!(
+            "  {:width$} {}",
+            "",
+            style(&source.url).dim(),
+            width = number.len()
+        );
+    }
+}
+
+/// The answer with markers, followed by the sources as plain lines for
+/// output that may be piped
+pub fn append_sources(text: &str, citations: &[Citation]) -> String {
+    let (mut cited, sources) = cite(text, citations);
+    if sources.is_empty() {
+        return cited;
+    }
+
+    cited = format!("{}\n\nSources:", cited.trim_end());
+    for (i, source) in sources.iter().enumerate() {
+        match source.title.is_empty() {
+            true => cited.push_str(&format!("\n[{}] {}", i + 1, source.url)),
+            false => cited.push_str(&format!("\n[{}] {} - {}", i + 1, source.title, source.url)),
+        }
+    }
+    cited
+}
@@ -0,0 +1,261 @@
+use console::{measure_text_width, style, Term};
+use serde_json::Value;
+
+use crate::{
+    config::Config,
+    provider::{llm_models, Provider},
+    session::Session,
+};
+
+/// Tools the chat model receives from one place
+struct ToolSet {
+    source: String,
+    enabled: bool,
+    /// `None` when the source is disabled or its MCP server failed to start
+    tools: Option<Vec<ToolInfo>>,
+}
+
+struct ToolInfo {
+    name: String,
+    description: String,
+    schema: Value,
+}
+
+/// Print the models, streaming mode, MCP servers, tools and context usage
+pub async fn print_status(
+    cfg: &mut Config,
+    provider: &Provider,
+    search_provider: Option<&Provider>,
+    streaming: bool,
+    session: &Session,
+) {
+    let model_id = provider.completion_model();
+    let search_model = search_provider
+        .and_then(|p| Some(format!("{} ({})", p.search_model()?, p.name())))
+        .unwrap_or_else(|| "None".to_string());
+
+    let sets = tool_sets(cfg).await;
+    let servers = sets
+        .iter()
+        .skip(1)
+        .map(|set| match (set.enabled, &set.tools) {
+            (false, _) => format!("{} (disabled)", set.source),
+            (true, None) => format!("{} (failed to start)", set.source),
+            (true, Some(tools)) => format!("{} ({} tools)", set.source, tools.len()),
+        })
+        .collect::<Vec<_>>();
+    let count = |set: &ToolSet| set.tools.as_ref().map_or(0, Vec::len);
+    let tools = sets.iter().map(count).sum::<usize>();
+    let builtin = sets.first().map_or(0, count);
+
+    let mut session_info = session.id.clone();
+    if !session.title.is_empty() {
+        session_info = format!("{} ({session_info})", session.title);
+    }
+    session_info.push_str(&match session.entries.len() {
+        1 => ", 1 message".to_string(),
+        count => format!(", {count} messages"),
+    });
+    if session.is_incognito() {
+        session_info.push_str(", incognito");
+    }
+
+    let context_window = llm_models::context_window(&model_id);
+    let used = session.estimated_tokens();
+    let threshold = cfg.compact_threshold();
+    let compaction = match threshold {
+        0 => "compaction off".to_string(),
+        percent => format!("compacted at {percent}%"),
+    };
+
+    let line = |label: &str, value: String| println!("  {:<14}{value}", style(label).bold());
+    println!("{}", style("Status:").bold().underlined());
+    line("Model", format!("{model_id} ({})", provider.name()));
+    line("Search model", search_model);
+    line(
+        "Streaming",
+        if streaming { "On" } else { "Off" }.to_string(),
+    );
+    line(
+        "MCP servers",
+        match servers.is_empty() {
+            true => "None".to_string(),
+            false => servers.join(", "),
+        },
+    );
+    line(
+        "Tools",
+        format!("{tools} ({builtin} built-in), see /tools for details"),
+    );
+    line("Session", session_info);
+    line(
+        "Context",
+        format!(
+            "~{used} of {context_window} tokens ({}%), {compaction}",
+            used * 100 / context_window.max(1)
+        ),
+    );
+}
+
+/// Print every tool the chat model receives, with its description and input
+pub async fn print_tools(cfg: &mut Config) {
+    let width = (Term::stdout().size().1 as usize).saturating_sub(4).max(20);
+
+    for (i, set) in tool_sets(cfg).await.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", style(format!("{}:", set.source)).bold().underlined());
+
+        let tools = match (set.enabled, &set.tools) {
+            (false, _) => {
+                println!("  {}", style("Disabled, turn it on with /mcp").dim());
+                continue;
+            }
+            (true, None) => {
+                println!("  {}", style("The server failed to start").red());
+                continue;
+            }
+            (true, Some(tools)) if tools.is_empty() => {
+                println!("  {}", style("No tools").dim());
+                continue;
+            }
+            (true, Some(tools)) => tools,
+        };
+
+        for tool in tools {
+            println!("  {}", style(&tool.name).bold());
+            for line in wrap(&tool.description, width) {
+                println!("    {line}");
+            }
+            for parameter in parameters(&tool.schema) {
+                for (i, line) in wrap(&parameter, width - 2).iter().enumerate() {
+                    let bullet = if i == 0 { "•" } else { " " };
+                    println!("    {}", style(format!("{bullet} {line}")).dim());
+                }
+            }
+        }
+    }
+
+    println!(
+        "\n{}",
+        style("Search models answer without tools.").dim().italic()
+    );
+}
+
+/// Built-in tools first, then the tools of each MCP server. Enabled servers
+/// are started, as they would be for the next message.
+async fn tool_sets(cfg: &mut Config) -> Vec<ToolSet> {
+    let builtin_tools = cfg.builtin_tools();
+    let reserved = builtin_tools.names();
+    let builtin = builtin_tools
+        .definitions()
+        .await
+        .into_iter()
+        .map(|definition| ToolInfo {
+            name: definition.name,
+            description: definition.description,
+            schema: definition.parameters,
+        })
+        .collect();
+
+    let mut sets = vec![ToolSet {
+        source: "Built-in Tools".to_string(),
+        enabled: true,
+        tools: Some(builtin),
+    }];
+
+    for client in cfg.mcp_clients() {
+        let enabled = client.is_enabled();
+        let tools = match enabled && client.initialize().await.is_ok() {
+            true => Some(
+                client
+                    .tools()
+                    .await
+                    .iter()
+                    // The model never gets MCP tools named like a built-in one
+                    .filter(|tool| !reserved.contains(&tool.name.as_str()))
+                    .map(|tool| ToolInfo {
+                        name: tool.name.clone(),
+                        description: tool.description.clone().unwrap_or_default(),
+                        schema: tool.input_schema.clone(),
+                    })
+                    .collect(),
+            ),
+            false => None,
+        };
+
+        sets.push(ToolSet {
+            source: client.name(),
+            enabled,
+            tools,
+        });
+    }
+
+    sets
+}
+
+/// One line per property of a JSON schema object: name, type, whether it is
+/// required and its description
+fn parameters(schema: &Value) -> Vec<String> {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return vec![];
+    };
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let kind = match property.get("type") {
+                Some(Value::String(kind)) => kind.clone(),
+                Some(Value::Array(kinds)) => kinds
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                _ => "any".to_string(),
+            };
+
+            let mut line = format!("{name}: {kind}");
+            if required.contains(&name.as_str()) {
+                line.push_str(", required");
+            }
+            if let Some(description) = property.get("description").and_then(Value::as_str) {
+                line.push_str(&format!(" - {description}"));
+            }
+            line
+        })
+        .collect()
+}
+
+/// Descriptions are plain text, so they are wrapped as is rather than
+/// rendered as markdown
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+
+    for paragraph in text.trim().lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && measure_text_width(&line) + 1 + measure_text_width(word) > width
+            {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use console::style;
 use indicatif::ProgressBar;
@@ -8,14 +8,22 @@ use crate::{
         self,
         utils::{on_the_fly_change_model, NO_MODELS_FOUND_MSG},
     },
+    attachments::{stdin::Piped, FileContext},
     config::Config,
+    editor::Editor,
     utils::{
         commands::copy_to_clipboard,
-        console::{get_select_theme, get_spinner_style},
+        console::{format_error, get_select_theme, get_spinner_style},
     },
 };
 
-pub async fn suggest(cfg: &Config, mut initial_query: Option<String>, select_model: bool) {
+pub async fn suggest(
+    cfg: &Config,
+    mut initial_query: Option<String>,
+    select_model: bool,
+    mut files: Vec<PathBuf>,
+    mut stdin: Option<Piped>,
+) {
     let mut provider = cfg
         .active_provider()
         .unwrap_or_else(|| {
@@ -43,16 +51,12 @@ pub async fn suggest(cfg: &Config, mut initial_query: Option<String>, select_mod
 
         let query = initial_query.clone().unwrap_or_else(|| {
             let msg = if last_suggestion.is_some() {
-                "How should this be revised?\n"
+                "How should this be revised?"
             } else {
-                "What would you like the shell command to do?\n"
+                "What would you like the shell command to do?"
             };
             let prompt = format! {"{} {}", style("?").green().bold(), style(msg).bold()};
-            let query = dialoguer::Input::<String>::new()
-                .with_prompt(prompt)
-                .allow_empty(false)
-                .interact()
-                .unwrap_or_default();
+            let query = Editor::ask(&prompt, "suggest");
 
             println!();
             query
@@ -64,6 +68,19 @@ pub async fn suggest(cfg: &Config, mut initial_query: Option<String>, select_mod
             return;
         }
 
+        // Files and piped input are attached to the first query only
+        let query = if files.is_empty() && stdin.is_none() {
+            query
+        } else {
+            let mut context = FileContext::collect(&std::mem::take(&mut files), false);
+            if let Some(piped) = stdin.take() {
+                context.add_piped(&piped);
+            }
+            context.print_summary();
+            println!();
+            context.apply(&query)
+        };
+
         // println!();
 
         let spinner = ProgressBar::new_spinner();
@@ -74,14 +91,14 @@ pub async fn suggest(cfg: &Config, mut initial_query: Option<String>, select_mod
         let result = if let Some(last_suggestion) = last_suggestion.to_owned() {
             provider.revise(&query, &last_suggestion).await
         } else {
-            provider.suggest(&query).await
+            provider.suggest(&query).await.map(|response| response.text)
         };
 
         let suggested_command = match result {
             Ok(command) => command,
             Err(e) => {
                 spinner.finish_and_clear();
-                eprintln!("{} {}\n", style("✗").red(), e);
+                eprintln!("{}\n", format_error(&e));
                 std::process::exit(1);
             }
         };
@@ -120,7 +137,7 @@ pub async fn suggest(cfg: &Config, mut initial_query: Option<String>, select_mod
                     break 'outer; // Exit
                 }
                 1 => {
-                    ai::explain(cfg, Some(suggested_command.clone()), false).await;
+                    ai::explain(cfg, Some(suggested_command.clone()), false, vec![], None).await;
                     std::thread::sleep(Duration::from_millis(500));
                     continue; // Continue to the next iteration of the inner loop
                 }
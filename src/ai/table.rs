@@ -0,0 +1,203 @@
+use console::{measure_text_width, style};
+use termimad::MadSkin;
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A GitHub-style table: a header row, a delimiter row and body rows
+pub struct Table {
+    header: Vec<String>,
+    aligns: Vec<Align>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Parse a table at the start of `lines` and the number of lines it takes
+    pub fn parse(lines: &[&str]) -> Option<(Self, usize)> {
+        let [header, delimiter, ..] = lines else {
+            return None;
+        };
+        if !header.contains('|') {
+            return None;
+        }
+
+        let header = cells(header);
+        let aligns = cells(delimiter)
+            .iter()
+            .map(|cell| align(cell))
+            .collect::<Option<Vec<_>>>()?;
+        if aligns.len() != header.len() {
+            return None;
+        }
+
+        let rows = lines[2..]
+            .iter()
+            .take_while(|line| !line.trim().is_empty() && line.contains('|'))
+            .map(|line| cells(line))
+            .collect::<Vec<_>>();
+        let len = rows.len() + 2;
+
+        Some((
+            Self {
+                header,
+                aligns,
+                rows,
+            },
+            len,
+        ))
+    }
+
+    /// Draw the table with borders, wrapping cells so it fits in `width`
+    pub fn render(&self, skin: &MadSkin, width: usize) -> String {
+        let columns = self.aligns.len();
+        let widths = self.widths(skin, width);
+        let border = |left: &str, middle: &str, right: &str| {
+            let line = widths
+                .iter()
+                .map(|width| "─".repeat(width + 2))
+                .collect::<Vec<_>>()
+                .join(middle);
+            format!("{}\n", style(format!("{left}{line}{right}")).dim())
+        };
+
+        let mut output = border("┌", "┬", "┐");
+        output.push_str(&self.render_row(skin, &self.header, &widths, true));
+        output.push_str(&border("├", "┼", "┤"));
+        for row in &self.rows {
+            let mut row = row.clone();
+            row.resize(columns, String::new());
+            output.push_str(&self.render_row(skin, &row, &widths, false));
+        }
+        output.push_str(&border("└", "┴", "┘"));
+        output
+    }
+
+    fn render_row(&self, skin: &MadSkin, row: &[String], widths: &[usize], header: bool) -> String {
+        let cells = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| {
+                skin.text(cell, Some(*width))
+                    .to_string()
+                    .lines()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let separator = style("│").dim().to_string();
+
+        let mut output = String::new();
+        for i in 0..height {
+            output.push_str(&separator);
+            for ((cell, width), align) in cells.iter().zip(widths).zip(&self.aligns) {
+                let line = cell.get(i).map(String::as_str).unwrap_or_default();
+                let line = match header {
+                    true => style(line).bold().to_string(),
+                    false => line.to_string(),
+                };
+                output.push_str(&format!(" {} {separator}", pad(&line, *width, *align)));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// Columns get their natural width when the table fits. Otherwise the
+    /// widest columns give up space first, down to their longest word.
+    fn widths(&self, skin: &MadSkin, width: usize) -> Vec<usize> {
+        let columns = self.aligns.len();
+        let mut widths = vec![1; columns];
+        let mut minimums = vec![1; columns];
+
+        for row in std::iter::once(&self.header).chain(&self.rows) {
+            for (i, cell) in row.iter().take(columns).enumerate() {
+                let rendered = skin.inline(cell).to_string();
+                widths[i] = widths[i].max(measure_text_width(&rendered));
+                let longest_word = rendered
+                    .split_whitespace()
+                    .map(measure_text_width)
+                    .max()
+                    .unwrap_or(0);
+                minimums[i] = minimums[i].max(longest_word);
+            }
+        }
+
+        // Borders and padding take 3 columns per cell and one at the end
+        let available = width.saturating_sub(3 * columns + 1);
+        while widths.iter().sum::<usize>() > available {
+            let shrinkable = (0..columns)
+                .filter(|i| widths[*i] > minimums[*i])
+                .max_by_key(|i| widths[*i])
+                .or_else(|| {
+                    (0..columns)
+                        .filter(|i| widths[*i] > 1)
+                        .max_by_key(|i| widths[*i])
+                });
+            match shrinkable {
+                Some(i) => widths[i] -= 1,
+                None => break,
+            }
+        }
+
+        widths
+    }
+}
+
+/// Cells of a row, split at pipes that are not escaped or in inline code
+fn cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line.strip_suffix('|').unwrap_or(line);
+
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut in_code = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '`' => {
+                in_code = !in_code;
+                cell.push(c);
+            }
+            '|' if !in_code => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+
+    cells
+}
+
+/// Alignment from a delimiter cell such as `:---:`, `None` if it isn't one
+fn align(cell: &str) -> Option<Align> {
+    let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+    if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+        return None;
+    }
+
+    Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+        (true, true) => Align::Center,
+        (false, true) => Align::Right,
+        _ => Align::Left,
+    })
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    let space = width.saturating_sub(measure_text_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, space),
+        Align::Center => (space / 2, space - space / 2),
+        Align::Right => (space, 0),
+    };
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
@@ -1,7 +1,11 @@
 use console::style;
 use dialoguer::{MultiSelect, Select};
 
-use crate::{config::Config, provider::Provider, utils::console::get_select_theme};
+use crate::{
+    config::Config,
+    provider::Provider,
+    utils::{console::get_select_theme, enums::ProviderName},
+};
 
 pub const NO_MODELS_FOUND_MSG: &str =
     "Unable to change model. Select a model in the Options menu to fix this issue.";
@@ -59,25 +63,67 @@ pub async fn on_the_fly_change_model(
         return None;
     };
 
+    use_model(cfg, provider_name, model_id, search)
+}
+
+/// Switch to a model given by ID or display name, as if it was selected
+/// with `on_the_fly_change_model`
+pub fn change_model(cfg: &mut Config, model: &str, search: bool) -> Option<Provider> {
+    let models = if search {
+        cfg.available_search_models()
+    } else {
+        cfg.available_completion_models()
+    };
+
+    let (provider_name, model_id, _) = models
+        .iter()
+        .find(|(_, id, name)| id == model || name.eq_ignore_ascii_case(model))?
+        .clone();
+    use_model(cfg, &provider_name, &model_id, search)
+}
+
+fn use_model(
+    cfg: &mut Config,
+    provider_name: &ProviderName,
+    model_id: &str,
+    search: bool,
+) -> Option<Provider> {
     if search {
         cfg.set_search_model(provider_name.to_owned(), model_id.to_owned());
     } else {
         cfg.set_completion_model(provider_name.to_owned(), model_id.to_owned());
     }
 
-    let provider = cfg.find_provider(provider_name);
-    match provider {
-        Some(p) => {
-            let mut provider = p.clone();
-            if search {
-                provider.set_search_model(model_id.to_string());
-            } else {
-                provider.set_completion_model(model_id.to_string());
-            }
-            Some(provider)
-        }
-        None => None,
+    let mut provider = cfg.find_provider(provider_name)?.clone();
+    if search {
+        provider.set_search_model(model_id.to_string());
+    } else {
+        provider.set_completion_model(model_id.to_string());
     }
+    Some(provider)
+}
+
+/// A provider set up with a completion model, found by ID or display name.
+/// The active model in the config is left unchanged.
+pub fn provider_for_model(cfg: &Config, model: &str) -> Option<Provider> {
+    let (provider_name, model_id, _) = cfg
+        .available_completion_models()
+        .iter()
+        .find(|(_, id, name)| id == model || name.eq_ignore_ascii_case(model))?;
+
+    let mut provider = cfg.find_provider(provider_name)?.clone();
+    provider.set_completion_model(model_id.to_owned());
+    Some(provider)
+}
+
+/// Enable or disable an MCP server by name. Returns whether it is now
+/// enabled, or `None` if there is no such server.
+pub fn toggle_mcp_client(cfg: &mut Config, name: &str) -> Option<bool> {
+    let client = cfg.find_mcp_client_mut(name)?;
+    let enabled = !client.is_enabled();
+    client.set_enabled(enabled);
+    cfg.save();
+    Some(enabled)
 }
 
 pub fn on_the_fly_select_mcp_client(cfg: &mut Config) {
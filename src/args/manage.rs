@@ -0,0 +1,313 @@
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
+
+use crate::utils::enums::ProviderName;
+
+use super::subcommand::SubCommand;
+
+#[derive(Clone)]
+pub enum ConfigAction {
+    Get(String),
+    Set(String, String),
+    List,
+}
+
+#[derive(Clone)]
+pub enum ProviderAction {
+    Add(ProviderName, bool), // Provider, Read key from stdin
+    Remove(ProviderName),
+    List,
+}
+
+#[derive(Clone)]
+pub enum ModelAction {
+    Set(String, bool), // Model ID or display name, Search model
+    List(bool),        // Search models
+}
+
+#[derive(Clone)]
+pub enum McpAction {
+    AddProgram(String, Vec<String>), // Program, Arguments
+    AddSse(String),                  // URL
+    Remove(String),
+    Enable(String),
+    Disable(String),
+    List,
+}
+
+#[derive(Clone)]
+pub enum SessionsAction {
+    List,
+    Show(String),
+    Delete(String),
+    Search(String),
+    Export(String, Option<String>, Option<String>), // ID, Format, Output file
+    Import(String),                                 // File ("-" for stdin)
+}
+
+pub fn config_command() -> Command {
+    let key_arg = Arg::new("key").required(true).help("The setting to access");
+
+    Command::new(SubCommand::Config)
+        .about(SubCommand::Config.about())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("get")
+                .about("Print the value of a setting")
+                .arg(key_arg.to_owned()),
+        )
+        .subcommand(
+            Command::new("set")
+                .about("Change the value of a setting")
+                .arg(key_arg)
+                .arg(Arg::new("value").required(true).help("The new value")),
+        )
+        .subcommand(Command::new("list").about("Print all settings as key=value lines"))
+}
+
+pub fn provider_command() -> Command {
+    let name_arg = Arg::new("name")
+        .required(true)
+        .value_parser(PossibleValuesParser::new(["openai", "anthropic"]))
+        .ignore_case(true)
+        .help("The provider name");
+
+    Command::new(SubCommand::Provider)
+        .about(SubCommand::Provider.about())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("add")
+                .about("Add a provider or replace its API key")
+                .arg(name_arg.to_owned())
+                .arg(
+                    Arg::new("key-stdin")
+                        .long("key-stdin")
+                        .action(ArgAction::SetTrue)
+                        .help("Read the API key from stdin instead of prompting for it"),
+                ),
+        )
+        .subcommand(
+            Command::new("remove")
+                .about("Remove a provider and its API key")
+                .arg(name_arg),
+        )
+        .subcommand(Command::new("list").about("List configured providers"))
+}
+
+pub fn model_command() -> Command {
+    let search_arg = Arg::new("search")
+        .short('s')
+        .long("search")
+        .action(ArgAction::SetTrue)
+        .help("Use search models instead of completion models");
+
+    Command::new(SubCommand::Model)
+        .about(SubCommand::Model.about())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("set")
+                .about("Set the active model")
+                .arg(
+                    Arg::new("id")
+                        .required(true)
+                        .help("The model ID (or display name)"),
+                )
+                .arg(search_arg.to_owned()),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("List available models")
+                .arg(search_arg),
+        )
+}
+
+pub fn mcp_command() -> Command {
+    let name_arg = Arg::new("name").required(true).help("The MCP server name");
+
+    Command::new(SubCommand::Mcp)
+        .about(SubCommand::Mcp.about())
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("add")
+                .about("Add an MCP server after testing the connection")
+                .arg(
+                    Arg::new("sse")
+                        .long("sse")
+                        .value_name("URL")
+                        .conflicts_with("command")
+                        .help("Connect to an SSE server instead of starting a program"),
+                )
+                .arg(
+                    Arg::new("command")
+                        .num_args(1..)
+                        .trailing_var_arg(true)
+                        .allow_hyphen_values(true)
+                        .required_unless_present("sse")
+                        .help("The program to start, followed by its arguments"),
+                ),
+        )
+        .subcommand(
+            Command::new("remove")
+                .about("Remove an MCP server")
+                .arg(name_arg.to_owned()),
+        )
+        .subcommand(
+            Command::new("enable")
+                .about("Enable an MCP server")
+                .arg(name_arg.to_owned()),
+        )
+        .subcommand(
+            Command::new("disable")
+                .about("Disable an MCP server")
+                .arg(name_arg),
+        )
+        .subcommand(Command::new("list").about("List configured MCP servers"))
+}
+
+pub fn sessions_command() -> Command {
+    let id_arg = Arg::new("id")
+        .required(true)
+        .help("The session ID (or a unique prefix of it)");
+
+    Command::new(SubCommand::Sessions)
+        .about(SubCommand::Sessions.about())
+        .subcommand_required(true)
+        .subcommand(Command::new("list").about("List saved sessions, most recent first"))
+        .subcommand(
+            Command::new("show")
+                .about("Print the conversation of a session")
+                .arg(id_arg.to_owned()),
+        )
+        .subcommand(
+            Command::new("delete")
+                .about("Delete a session")
+                .arg(id_arg.to_owned()),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export a session as Markdown, JSON or HTML")
+                .arg(id_arg)
+                .arg(
+                    Arg::new("format")
+                        .short('f')
+                        .long("format")
+                        .value_parser(PossibleValuesParser::new(["markdown", "json", "html"]))
+                        .help(
+                            "The output format (guessed from the output file, Markdown otherwise)",
+                        ),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Write to a file instead of stdout"),
+                ),
+        )
+        .subcommand(
+            Command::new("import")
+                .about("Import a session that was exported as JSON")
+                .arg(
+                    Arg::new("file")
+                        .required(true)
+                        .help("The JSON file to import (- to read from stdin)"),
+                ),
+        )
+        .subcommand(
+            Command::new("search")
+                .about("List sessions that contain the query")
+                .arg(
+                    Arg::new("query")
+                        .required(true)
+                        .num_args(1..)
+                        .help("The text to search for"),
+                ),
+        )
+}
+
+impl ConfigAction {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.subcommand() {
+            Some(("get", args)) => ConfigAction::Get(string(args, "key")),
+            Some(("set", args)) => ConfigAction::Set(string(args, "key"), string(args, "value")),
+            _ => ConfigAction::List,
+        }
+    }
+}
+
+impl ProviderAction {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.subcommand() {
+            Some(("add", args)) => {
+                ProviderAction::Add(provider_name(args), args.get_flag("key-stdin"))
+            }
+            Some(("remove", args)) => ProviderAction::Remove(provider_name(args)),
+            _ => ProviderAction::List,
+        }
+    }
+}
+
+impl ModelAction {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.subcommand() {
+            Some(("set", args)) => ModelAction::Set(string(args, "id"), args.get_flag("search")),
+            Some((_, args)) => ModelAction::List(args.get_flag("search")),
+            None => ModelAction::List(false),
+        }
+    }
+}
+
+impl McpAction {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.subcommand() {
+            Some(("add", args)) => match args.get_one::<String>("sse") {
+                Some(url) => McpAction::AddSse(url.to_owned()),
+                None => {
+                    let mut command = args
+                        .get_many::<String>("command")
+                        .unwrap_or_default()
+                        .cloned();
+                    let program = command.next().unwrap_or_default();
+                    McpAction::AddProgram(program, command.collect())
+                }
+            },
+            Some(("remove", args)) => McpAction::Remove(string(args, "name")),
+            Some(("enable", args)) => McpAction::Enable(string(args, "name")),
+            Some(("disable", args)) => McpAction::Disable(string(args, "name")),
+            _ => McpAction::List,
+        }
+    }
+}
+
+impl SessionsAction {
+    pub fn from_matches(matches: &ArgMatches) -> Self {
+        match matches.subcommand() {
+            Some(("show", args)) => SessionsAction::Show(string(args, "id")),
+            Some(("delete", args)) => SessionsAction::Delete(string(args, "id")),
+            Some(("export", args)) => SessionsAction::Export(
+                string(args, "id"),
+                args.get_one::<String>("format").cloned(),
+                args.get_one::<String>("output").cloned(),
+            ),
+            Some(("import", args)) => SessionsAction::Import(string(args, "file")),
+            Some(("search", args)) => {
+                let query = args
+                    .get_many::<String>("query")
+                    .unwrap_or_default()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                SessionsAction::Search(query.join(" "))
+            }
+            _ => SessionsAction::List,
+        }
+    }
+}
+
+fn string(args: &ArgMatches, id: &str) -> String {
+    args.get_one::<String>(id).cloned().unwrap_or_default()
+}
+
+fn provider_name(args: &ArgMatches) -> ProviderName {
+    string(args, "name")
+        .parse()
+        .expect("Provider name is validated by clap")
+}
@@ -1,15 +1,29 @@
+mod manage;
 mod subcommand;
 
-use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, ArgMatches, Command};
 use subcommand::SubCommand;
 
+use crate::utils::enums::OutputFormat;
+
+pub use manage::{ConfigAction, McpAction, ModelAction, ProviderAction, SessionsAction};
+
 #[derive(Clone)]
 pub enum Args {
     Chat((&'static str, ChatArgs)),
+    Ask((&'static str, ChatArgs)),
+    Compare((&'static str, ChatArgs)),
     Suggest((&'static str, ChatArgs)),
     Explain((&'static str, ChatArgs)),
     Options,
     Changelog,
+    Config(ConfigAction),
+    Provider(ProviderAction),
+    Model(ModelAction),
+    Mcp(McpAction),
+    Sessions(SessionsAction),
     None,
 }
 
@@ -30,6 +44,20 @@ impl Args {
             .action(ArgAction::SetTrue)
             .help("Search the web (requires OpenAI or Perplexity API key)");
 
+        let file_arg = Arg::new("file")
+            .short('f')
+            .long("file")
+            .value_name("PATH")
+            .action(ArgAction::Append)
+            .help("Attach a file or directory as context (can be repeated)");
+
+        let output_arg = Arg::new("output")
+            .long("output")
+            .value_name("FORMAT")
+            .value_parser(PossibleValuesParser::new(["text", "json"]))
+            .default_value("text")
+            .help("Print the answer as text or as a JSON object (NDJSON events when streaming)");
+
         let prompt_arg = Arg::new("prompt")
             .help("The prompt to send to the AI")
             .num_args(1..);
@@ -44,33 +72,122 @@ impl Args {
                         "Specify the AI model to use (You can also use /model in an active chat)",
                     ))
                     .arg(search_arg.to_owned())
+                    .arg(
+                        Arg::new("resume")
+                            .short('r')
+                            .long("resume")
+                            .value_name("ID")
+                            .num_args(0..=1)
+                            .default_missing_value("")
+                            .help("Resume a saved session (the most recent one if no ID is given)"),
+                    )
+                    .arg(
+                        Arg::new("incognito")
+                            .short('i')
+                            .long("incognito")
+                            .action(ArgAction::SetTrue)
+                            .help("Do not save the session to disk"),
+                    )
+                    .arg(file_arg.to_owned())
+                    .arg(output_arg.to_owned())
+                    .arg(prompt_arg.to_owned()),
+            )
+            .subcommand(
+                Command::new(SubCommand::Ask)
+                    .about(SubCommand::Ask.about())
+                    .arg(search_arg.to_owned())
+                    .arg(file_arg.to_owned())
+                    .arg(output_arg.to_owned())
+                    .arg(prompt_arg.to_owned()),
+            )
+            .subcommand(
+                Command::new(SubCommand::Compare)
+                    .about(SubCommand::Compare.about())
+                    .arg(
+                        Arg::new("models")
+                            .short('m')
+                            .long("models")
+                            .value_name("MODELS")
+                            .required(true)
+                            .help("Models to compare by ID or name, separated by commas"),
+                    )
+                    .arg(
+                        Arg::new("columns")
+                            .short('c')
+                            .long("columns")
+                            .action(ArgAction::SetTrue)
+                            .help("Print the answers side by side"),
+                    )
+                    .arg(file_arg.to_owned())
                     .arg(prompt_arg.to_owned()),
             )
             .subcommand(
                 Command::new(SubCommand::Suggest)
                     .about(SubCommand::Suggest.about())
                     .arg(model_arg.to_owned())
+                    .arg(file_arg.to_owned())
+                    .arg(output_arg.to_owned())
                     .arg(prompt_arg.to_owned()),
             )
             .subcommand(
                 Command::new(SubCommand::Explain)
                     .about(SubCommand::Explain.about())
                     .arg(model_arg.to_owned())
+                    .arg(file_arg.to_owned())
+                    .arg(output_arg.to_owned())
                     .arg(prompt_arg.to_owned()),
             )
             .subcommand(Command::new(SubCommand::Options).about(SubCommand::Options.about()))
             .subcommand(Command::new(SubCommand::Changelog).about(SubCommand::Changelog.about()))
+            .subcommand(manage::config_command())
+            .subcommand(manage::provider_command())
+            .subcommand(manage::model_command())
+            .subcommand(manage::mcp_command())
+            .subcommand(manage::sessions_command())
             .get_matches();
 
-        match matches.subcommand_name() {
-            Some("chat") => Args::Chat((SubCommand::Chat.as_str(), ChatArgs(matches))),
-            Some("suggest") => Args::Suggest((SubCommand::Suggest.as_str(), ChatArgs(matches))),
-            Some("explain") => Args::Explain((SubCommand::Explain.as_str(), ChatArgs(matches))),
-            Some("options") => Args::Options,
-            Some("changelog") => Args::Changelog,
+        match matches.subcommand() {
+            Some(("chat", _)) => Args::Chat((SubCommand::Chat.as_str(), ChatArgs(matches))),
+            Some(("ask", _)) => Args::Ask((SubCommand::Ask.as_str(), ChatArgs(matches))),
+            Some(("compare", _)) => {
+                Args::Compare((SubCommand::Compare.as_str(), ChatArgs(matches)))
+            }
+            Some(("suggest", _)) => {
+                Args::Suggest((SubCommand::Suggest.as_str(), ChatArgs(matches)))
+            }
+            Some(("explain", _)) => {
+                Args::Explain((SubCommand::Explain.as_str(), ChatArgs(matches)))
+            }
+            Some(("options", _)) => Args::Options,
+            Some(("changelog", _)) => Args::Changelog,
+            Some(("config", args)) => Args::Config(ConfigAction::from_matches(args)),
+            Some(("provider", args)) => Args::Provider(ProviderAction::from_matches(args)),
+            Some(("model", args)) => Args::Model(ModelAction::from_matches(args)),
+            Some(("mcp", args)) => Args::Mcp(McpAction::from_matches(args)),
+            Some(("sessions", args)) => Args::Sessions(SessionsAction::from_matches(args)),
             _ => Args::None,
         }
     }
+
+    /// The subcommand and arguments of commands that talk to the AI
+    pub fn chat_args(&self) -> Option<(&'static str, &ChatArgs)> {
+        match self {
+            Args::Chat((command, args))
+            | Args::Ask((command, args))
+            | Args::Suggest((command, args))
+            | Args::Explain((command, args)) => Some((command, args)),
+            _ => None,
+        }
+    }
+
+    /// Management commands print machine-readable output, so they must not be
+    /// mixed with the welcome banner.
+    pub fn is_management(&self) -> bool {
+        matches!(
+            self,
+            Args::Config(_) | Args::Provider(_) | Args::Model(_) | Args::Mcp(_) | Args::Sessions(_)
+        )
+    }
 }
 
 impl ChatArgs {
@@ -92,6 +209,84 @@ impl ChatArgs {
         }
     }
 
+    /// `Some(None)` resumes the most recent session
+    pub fn resume(&self) -> Option<Option<String>> {
+        match self.0.subcommand() {
+            Some((_, args)) => {
+                if args.try_contains_id("resume").is_err() {
+                    return None;
+                }
+
+                args.get_one::<String>("resume")
+                    .map(|id| Some(id.to_owned()).filter(|id| !id.is_empty()))
+            }
+            None => None,
+        }
+    }
+
+    pub fn incognito(&self) -> bool {
+        match self.0.subcommand() {
+            Some((_, args)) => {
+                args.try_contains_id("incognito").unwrap_or(false) && args.get_flag("incognito")
+            }
+            None => false,
+        }
+    }
+
+    /// Models of `compare`, separated by commas
+    pub fn models(&self) -> Option<String> {
+        match self.0.subcommand() {
+            Some((_, args)) => {
+                if args.try_contains_id("models").is_err() {
+                    return None;
+                }
+
+                args.get_one::<String>("models").cloned()
+            }
+            None => None,
+        }
+    }
+
+    pub fn columns(&self) -> bool {
+        match self.0.subcommand() {
+            Some((_, args)) => {
+                args.try_contains_id("columns").unwrap_or(false) && args.get_flag("columns")
+            }
+            None => false,
+        }
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        match self.0.subcommand() {
+            Some((_, args)) => {
+                if args.try_contains_id("file").is_err() {
+                    return vec![];
+                }
+
+                args.get_many::<String>("file")
+                    .unwrap_or_default()
+                    .map(PathBuf::from)
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+
+    pub fn output(&self) -> OutputFormat {
+        match self.0.subcommand() {
+            Some((_, args)) => {
+                if args.try_contains_id("output").is_err() {
+                    return OutputFormat::default();
+                }
+
+                args.get_one::<String>("output")
+                    .and_then(|format| format.parse().ok())
+                    .unwrap_or_default()
+            }
+            None => OutputFormat::default(),
+        }
+    }
+
     pub fn prompt(&self) -> Option<String> {
         match self.0.subcommand() {
             Some((_, args)) => {
@@ -2,20 +2,34 @@ use clap::builder::Str;
 
 pub enum SubCommand {
     Chat,
+    Ask,
+    Compare,
     Suggest,
     Explain,
     Options,
     Changelog,
+    Config,
+    Provider,
+    Model,
+    Mcp,
+    Sessions,
 }
 
 impl std::fmt::Display for SubCommand {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             SubCommand::Chat => write!(f, "chat"),
+            SubCommand::Ask => write!(f, "ask"),
+            SubCommand::Compare => write!(f, "compare"),
             SubCommand::Suggest => write!(f, "suggest"),
             SubCommand::Explain => write!(f, "explain"),
             SubCommand::Options => write!(f, "options"),
             SubCommand::Changelog => write!(f, "changelog"),
+            SubCommand::Config => write!(f, "config"),
+            SubCommand::Provider => write!(f, "provider"),
+            SubCommand::Model => write!(f, "model"),
+            SubCommand::Mcp => write!(f, "mcp"),
+            SubCommand::Sessions => write!(f, "sessions"),
         }
     }
 }
@@ -24,10 +38,17 @@ impl From<SubCommand> for Str {
     fn from(val: SubCommand) -> Self {
         match val {
             SubCommand::Chat => Str::from("chat"),
+            SubCommand::Ask => Str::from("ask"),
+            SubCommand::Compare => Str::from("compare"),
             SubCommand::Suggest => Str::from("suggest"),
             SubCommand::Explain => Str::from("explain"),
             SubCommand::Options => Str::from("options"),
             SubCommand::Changelog => Str::from("changelog"),
+            SubCommand::Config => Str::from("config"),
+            SubCommand::Provider => Str::from("provider"),
+            SubCommand::Model => Str::from("model"),
+            SubCommand::Mcp => Str::from("mcp"),
+            SubCommand::Sessions => Str::from("sessions"),
         }
     }
 }
@@ -36,20 +57,34 @@ impl SubCommand {
     pub fn as_str(&self) -> &'static str {
         match self {
             SubCommand::Chat => "chat",
+            SubCommand::Ask => "ask",
+            SubCommand::Compare => "compare",
             SubCommand::Suggest => "suggest",
             SubCommand::Explain => "explain",
             SubCommand::Options => "options",
             SubCommand::Changelog => "changelog",
+            SubCommand::Config => "config",
+            SubCommand::Provider => "provider",
+            SubCommand::Model => "model",
+            SubCommand::Mcp => "mcp",
+            SubCommand::Sessions => "sessions",
         }
     }
 
     pub fn about(&self) -> &'static str {
         match self {
             SubCommand::Chat => "Start a chat with the AI",
+            SubCommand::Ask => "Ask a single question and print only the answer",
+            SubCommand::Compare => "Send one prompt to several models and compare the answers",
             SubCommand::Suggest => "Get CLI command suggestions from the AI",
             SubCommand::Explain => "Get CLI command explanations from the AI",
             SubCommand::Options => "Open the options menu",
             SubCommand::Changelog => "Print the latest changelog",
+            SubCommand::Config => "Get, set and list settings",
+            SubCommand::Provider => "Add, remove and list providers",
+            SubCommand::Model => "Set and list the active models",
+            SubCommand::Mcp => "Add, remove, enable, disable and list MCP servers",
+            SubCommand::Sessions => "List, show, search and delete saved chat sessions",
         }
     }
 }
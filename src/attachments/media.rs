@@ -0,0 +1,194 @@
+use std::{fmt, fs, path::Path};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use rig::{
+    message::{
+        ContentFormat, DocumentMediaType, ImageDetail, ImageMediaType, Message, UserContent,
+    },
+    OneOrMany,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{provider::llm_models, utils::enums::ProviderName};
+
+/// Largest image or PDF that is sent to a provider
+const MAX_MEDIA_BYTES: u64 = 20 * 1024 * 1024;
+/// Rough number of tokens a provider charges for an image
+const IMAGE_TOKENS: usize = 1_600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Image,
+    Pdf,
+}
+
+impl fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaKind::Image => write!(f, "images"),
+            MediaKind::Pdf => write!(f, "PDF files"),
+        }
+    }
+}
+
+impl MediaKind {
+    /// Media kind of a file, based on its extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "webp" => Some(MediaKind::Image),
+            "pdf" => Some(MediaKind::Pdf),
+            _ => None,
+        }
+    }
+}
+
+/// An image or PDF that is sent to the model as a multimodal content part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String,
+    pub kind: MediaKind,
+    pub media_type: String,
+    /// Base64 encoded file contents
+    pub data: String,
+}
+
+impl Attachment {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let kind = MediaKind::from_path(path).ok_or_else(|| {
+            format!(
+                "{} is not a PNG, JPEG or WebP image or a PDF file",
+                path.display()
+            )
+        })?;
+
+        let size = fs::metadata(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?
+            .len();
+        if size > MAX_MEDIA_BYTES {
+            return Err(format!(
+                "{} is larger than {} MB",
+                path.display(),
+                MAX_MEDIA_BYTES / 1024 / 1024
+            ));
+        }
+
+        let bytes =
+            fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let media_type = match (kind, &bytes[..bytes.len().min(12)]) {
+            (MediaKind::Image, [0x89, b'P', b'N', b'G', ..]) => "image/png",
+            (MediaKind::Image, [0xFF, 0xD8, 0xFF, ..]) => "image/jpeg",
+            (MediaKind::Image, [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P']) => {
+                "image/webp"
+            }
+            (MediaKind::Pdf, [b'%', b'P', b'D', b'F', ..]) => "application/pdf",
+            _ => {
+                return Err(format!(
+                    "{} does not look like a valid {} file",
+                    path.display(),
+                    path.extension().unwrap_or_default().to_string_lossy()
+                ))
+            }
+        };
+
+        Ok(Self {
+            name: path.display().to_string(),
+            kind,
+            media_type: media_type.to_string(),
+            data: BASE64_STANDARD.encode(&bytes),
+        })
+    }
+
+    /// Decoded size in bytes
+    pub fn size(&self) -> usize {
+        self.data.len() / 4 * 3
+    }
+
+    pub fn estimated_tokens(&self) -> usize {
+        match self.kind {
+            MediaKind::Image => IMAGE_TOKENS,
+            MediaKind::Pdf => self.size() / 4,
+        }
+    }
+
+    fn to_content(&self, provider: &ProviderName) -> UserContent {
+        match self.kind {
+            MediaKind::Image => {
+                let media_type = match self.media_type.as_str() {
+                    "image/jpeg" => ImageMediaType::JPEG,
+                    "image/webp" => ImageMediaType::WEBP,
+                    _ => ImageMediaType::PNG,
+                };
+
+                // OpenAI takes images as URLs, so the data is wrapped in a data URL
+                let data = match provider {
+                    ProviderName::OpenAI => {
+                        format!("data:{};base64,{}", self.media_type, self.data)
+                    }
+                    ProviderName::Anthropic => self.data.to_owned(),
+                };
+
+                UserContent::image(
+                    data,
+                    Some(ContentFormat::Base64),
+                    Some(media_type),
+                    Some(ImageDetail::Auto),
+                )
+            }
+            MediaKind::Pdf => UserContent::document(
+                self.data.to_owned(),
+                Some(ContentFormat::Base64),
+                Some(DocumentMediaType::PDF),
+            ),
+        }
+    }
+}
+
+/// A user message with its attachments as separate content parts. Attachments
+/// the model cannot read are replaced by a note so older history keeps working
+/// after switching to a text-only model.
+pub fn user_message(
+    text: &str,
+    attachments: &[Attachment],
+    provider: &ProviderName,
+    model_id: &str,
+) -> Message {
+    if attachments.is_empty() {
+        return Message::user(text);
+    }
+
+    let mut content = vec![UserContent::text(text)];
+    for attachment in attachments {
+        if llm_models::accepts(model_id, provider, attachment.kind) {
+            content.push(attachment.to_content(provider));
+        } else {
+            content.push(UserContent::text(format!(
+                "[{} was attached here but is not available to this model]",
+                attachment.name
+            )));
+        }
+    }
+
+    Message::User {
+        content: OneOrMany::many(content).expect("Content contains at least the text"),
+    }
+}
+
+/// Refuse attachments the model cannot read before anything is sent
+pub fn check_supported(
+    attachments: &[Attachment],
+    provider: &ProviderName,
+    model_id: &str,
+) -> Result<(), String> {
+    match attachments
+        .iter()
+        .find(|a| !llm_models::accepts(model_id, provider, a.kind))
+    {
+        Some(attachment) => Err(format!(
+            "{model_id} does not accept {} ({})",
+            attachment.kind, attachment.name
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,279 @@
+pub mod media;
+pub mod stdin;
+
+use std::{
+    fmt::{self, Write},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use console::style;
+use ignore::WalkBuilder;
+
+use media::{Attachment, MediaKind};
+use stdin::Piped;
+
+/// Maximum size of all attached files together
+const MAX_TOTAL_BYTES: usize = 256 * 1024;
+/// Bytes inspected to decide whether a file is binary
+const BINARY_CHECK_BYTES: usize = 8 * 1024;
+
+pub enum Skipped {
+    Binary,
+    Ignored,
+    TooLarge,
+    Unreadable(String),
+}
+
+impl fmt::Display for Skipped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Skipped::Binary => write!(f, "binary file"),
+            Skipped::Ignored => write!(f, "ignored by .gitignore"),
+            Skipped::TooLarge => write!(f, "size limit reached"),
+            Skipped::Unreadable(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FileContext {
+    /// File contents, each wrapped in a `<file>` element
+    pub text: String,
+    pub attached: Vec<(PathBuf, usize)>,
+    pub skipped: Vec<(PathBuf, Skipped)>,
+    /// Files of expanded directories that were not read once the size cap was hit
+    pub omitted: usize,
+    /// Images and PDFs given explicitly, sent as multimodal content
+    pub media: Vec<Attachment>,
+    /// Set once a file didn't fit under the size cap
+    full: bool,
+}
+
+impl FileContext {
+    /// Read files and directories. Directories are expanded recursively while
+    /// respecting `.gitignore`. Files are only read while they fit under the
+    /// size cap. Once the cap is hit, the remaining files of directories are
+    /// only counted as omitted. With `with_media`, images and PDFs named directly
+    /// become attachments.
+    pub fn collect(paths: &[PathBuf], with_media: bool) -> Self {
+        let mut context = FileContext::default();
+
+        for path in paths {
+            if path.is_dir() {
+                let walker = WalkBuilder::new(path)
+                    .sort_by_file_name(|a, b| a.cmp(b))
+                    .build();
+
+                let files = walker
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_some_and(|t| t.is_file()));
+                for entry in files {
+                    if context.full {
+                        context.omitted += 1;
+                    } else {
+                        context.add_file(entry.path());
+                    }
+                }
+            } else if with_media && MediaKind::from_path(path).is_some() {
+                match Attachment::load(path) {
+                    Ok(attachment) => context.media.push(attachment),
+                    Err(e) => context
+                        .skipped
+                        .push((path.to_owned(), Skipped::Unreadable(e))),
+                }
+            } else if is_ignored(path) {
+                context.skipped.push((path.to_owned(), Skipped::Ignored));
+            } else {
+                context.add_file(path);
+            }
+        }
+
+        context
+    }
+
+    /// Add text that was piped into termai
+    pub fn add_piped(&mut self, piped: &Piped) {
+        let _ = writeln!(self.text, "<stdin>\n{}\n</stdin>\n", piped.text.trim_end());
+
+        let name = match piped.truncated {
+            true => "stdin (truncated at the stdin_limit_kb setting)",
+            false => "stdin",
+        };
+        self.attached.push((PathBuf::from(name), piped.text.len()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attached.is_empty()
+            && self.skipped.is_empty()
+            && self.media.is_empty()
+            && self.omitted == 0
+    }
+
+    /// Append the file contents to a prompt
+    pub fn apply(&self, prompt: &str) -> String {
+        if self.text.is_empty() {
+            return prompt.to_string();
+        }
+
+        format!("{prompt}\n\n{}", self.text.trim_end())
+    }
+
+    /// Split a prompt built by `apply` back into what was typed and the
+    /// appended file contents
+    pub fn split(prompt: &str) -> (&str, &str) {
+        let start = ["\n\n<file path=\"", "\n\n<stdin>\n"]
+            .iter()
+            .filter_map(|marker| prompt.find(marker))
+            .min()
+            .unwrap_or(prompt.len());
+
+        prompt.split_at(start)
+    }
+
+    /// Tell the user what was attached and what was left out
+    pub fn print_summary(&self) {
+        for (path, bytes) in &self.attached {
+            println!(
+                "{} {}",
+                style("📎").dim(),
+                style(format!("{} ({})", path.display(), format_size(*bytes))).dim()
+            );
+        }
+
+        for attachment in &self.media {
+            print_attachment(attachment);
+        }
+
+        for (path, reason) in &self.skipped {
+            println!(
+                "{} {}",
+                style("✗").red(),
+                style(format!("Skipped {} ({reason})", path.display())).dim()
+            );
+        }
+
+        if let Some(note) = self.omitted_note() {
+            println!("{} {}", style("✗").red(), style(note).dim());
+        }
+    }
+
+    /// One line for the files that were never read because of the size cap
+    pub fn omitted_note(&self) -> Option<String> {
+        match self.omitted {
+            0 => None,
+            1 => Some(format!("1 more file omitted ({})", Skipped::TooLarge)),
+            n => Some(format!("{n} more files omitted ({})", Skipped::TooLarge)),
+        }
+    }
+
+    fn add_file(&mut self, path: &Path) {
+        let budget = MAX_TOTAL_BYTES.saturating_sub(self.text.len());
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.len() > budget as u64 => {
+                self.full = true;
+                self.skipped.push((path.to_owned(), Skipped::TooLarge));
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                let reason = Skipped::Unreadable(e.to_string());
+                self.skipped.push((path.to_owned(), reason));
+                return;
+            }
+        }
+
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let reason = Skipped::Unreadable(e.to_string());
+                self.skipped.push((path.to_owned(), reason));
+                return;
+            }
+        };
+
+        let looks_binary = bytes[..bytes.len().min(BINARY_CHECK_BYTES)].contains(&0);
+        let content = match String::from_utf8(bytes) {
+            Ok(content) if !looks_binary => content,
+            _ => {
+                self.skipped.push((path.to_owned(), Skipped::Binary));
+                return;
+            }
+        };
+
+        if self.text.len() + content.len() > MAX_TOTAL_BYTES {
+            self.full = true;
+            self.skipped.push((path.to_owned(), Skipped::TooLarge));
+            return;
+        }
+
+        let _ = writeln!(
+            self.text,
+            "<file path=\"{}\">\n{}\n</file>\n",
+            path.display(),
+            content.trim_end()
+        );
+        self.attached.push((path.to_owned(), content.len()));
+    }
+}
+
+/// Paths mentioned as `@path` in the input. Only existing paths count, so
+/// things like e-mail addresses and handles are left alone.
+pub fn mentions(input: &str) -> Vec<PathBuf> {
+    input
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(|path| path.trim_end_matches([',', '.', ':', ';', '?', '!', ')']))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Check a single file against the ignore rules of its directory
+pub fn is_ignored(path: &Path) -> bool {
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    let parent = if parent.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        parent
+    };
+
+    let Some(file_name) = path.file_name() else {
+        return false;
+    };
+
+    let visible = WalkBuilder::new(parent)
+        .max_depth(Some(1))
+        .hidden(false)
+        .build()
+        .filter_map(|e| e.ok())
+        .any(|e| e.file_name() == file_name);
+
+    !visible
+}
+
+pub fn print_attachment(attachment: &Attachment) {
+    println!(
+        "{} {}",
+        style("📎").dim(),
+        style(format!(
+            "{} ({})",
+            attachment.name,
+            format_size(attachment.size())
+        ))
+        .dim()
+    );
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::{self, IsTerminal, Read};
+
+/// Text piped into termai
+pub struct Piped {
+    pub text: String,
+    /// The input was cut off at the size limit
+    pub truncated: bool,
+}
+
+impl Piped {
+    /// Read stdin when it is not a terminal, up to `limit` bytes. Afterwards
+    /// stdin is reconnected to the terminal so interactive prompts keep working.
+    pub fn read(limit: usize) -> Option<Self> {
+        if io::stdin().is_terminal() {
+            return None;
+        }
+
+        let mut bytes = vec![];
+        let result = raw_stdin().take(limit as u64 + 1).read_to_end(&mut bytes);
+        reattach_terminal();
+
+        if result.is_err() || bytes.iter().all(|b| b.is_ascii_whitespace()) {
+            return None;
+        }
+
+        let truncated = bytes.len() > limit;
+        bytes.truncate(limit);
+
+        Some(Self {
+            text: String::from_utf8_lossy(&bytes).into_owned(),
+            truncated,
+        })
+    }
+}
+
+/// Unbuffered stdin, so nothing past the limit is left behind in the buffer
+/// of `io::stdin` once it points at the terminal
+#[cfg(unix)]
+fn raw_stdin() -> Box<dyn Read> {
+    use std::{fs::File, os::fd::FromRawFd};
+
+    let fd = unsafe { libc::dup(libc::STDIN_FILENO) };
+    if fd < 0 {
+        return Box::new(io::stdin());
+    }
+
+    Box::new(unsafe { File::from_raw_fd(fd) })
+}
+
+#[cfg(not(unix))]
+fn raw_stdin() -> Box<dyn Read> {
+    Box::new(io::stdin())
+}
+
+#[cfg(unix)]
+fn reattach_terminal() {
+    use std::{fs::File, os::fd::AsRawFd};
+
+    // Without a terminal (cron, CI) follow-up prompts see end of input
+    if let Ok(tty) = File::open("/dev/tty") {
+        unsafe { libc::dup2(tty.as_raw_fd(), libc::STDIN_FILENO) };
+    }
+}
+
+#[cfg(not(unix))]
+fn reattach_terminal() {}
@@ -8,9 +8,15 @@ use rig::{
 
 use anyhow::Result;
 
-use crate::client::{enums::StopReason, traits::CompetionResponseExt};
+use crate::{
+    client::{
+        enums::StopReason, response::Usage, traits::CompetionResponseExt, ChatResponse,
+        ToolCallRecord,
+    },
+    tools::BuiltinTools,
+};
 
-use super::util::confirm_tool_call;
+use super::util::approve_tool_call;
 
 pub struct MultiTurnAgent<M>
 where
@@ -19,6 +25,7 @@ where
 {
     agent: Agent<M>,
     chat_history: Vec<Message>,
+    tools: BuiltinTools,
 }
 
 impl<M> MultiTurnAgent<M>
@@ -26,10 +33,11 @@ where
     M: CompletionModel,
     <M as CompletionModel>::Response: CompetionResponseExt,
 {
-    pub fn new(agent: Agent<M>, chat_history: Vec<Message>) -> Self {
+    pub fn new(agent: Agent<M>, chat_history: Vec<Message>, tools: BuiltinTools) -> Self {
         Self {
             agent,
             chat_history,
+            tools,
         }
     }
 
@@ -37,8 +45,10 @@ where
         &mut self,
         prompt: impl Into<Message> + Send,
         spinner: Option<&ProgressBar>,
-    ) -> Result<String, PromptError> {
+    ) -> Result<ChatResponse, PromptError> {
         let mut current_prompt: Message = prompt.into();
+        let mut tool_calls = vec![];
+        let mut usage = Usage::default();
         loop {
             let res = self
                 .agent
@@ -47,6 +57,7 @@ where
                 .send()
                 .await?;
             self.chat_history.push(current_prompt.to_owned());
+            usage += res.raw_response.usage();
 
             let mut final_text = None;
 
@@ -72,16 +83,19 @@ where
                             function: ToolFunction { name, arguments },
                         } = tool_call;
 
-                        let confirmation = confirm_tool_call(&name, spinner);
+                        let tool_result =
+                            match approve_tool_call(&self.tools, &name, &arguments, spinner) {
+                                Ok(()) => {
+                                    match self.agent.tools.call(&name, arguments.to_string()).await
+                                    {
+                                        Ok(result) => result,
+                                        Err(e) => e.to_string(),
+                                    }
+                                }
+                                Err(reason) => reason,
+                            };
 
-                        let tool_result = if confirmation {
-                            match self.agent.tools.call(&name, arguments.to_string()).await {
-                                Ok(result) => result,
-                                Err(_) => "Error calling tool".to_string(),
-                            }
-                        } else {
-                            "Cancelled by user".to_string()
-                        };
+                        tool_calls.push(ToolCallRecord::new(&name, &arguments, &tool_result));
 
                         current_prompt = Message::User {
                             content: OneOrMany::one(UserContent::tool_result(
@@ -97,11 +111,15 @@ where
             }
 
             // Stop if stop reason is not tool call or None
-            if !matches!(
-                res.raw_response.stop_reason(),
-                StopReason::ToolCall | StopReason::None
-            ) {
-                return Ok(final_text.unwrap_or_default());
+            let stop_reason = res.raw_response.stop_reason();
+            if !matches!(stop_reason, StopReason::ToolCall | StopReason::None) {
+                return Ok(ChatResponse {
+                    text: final_text.unwrap_or_default(),
+                    tool_calls,
+                    stop_reason,
+                    usage,
+                    ..Default::default()
+                });
             }
         }
     }
@@ -2,7 +2,7 @@ use async_stream::stream;
 use futures::{Stream, StreamExt};
 use rig::{
     agent::Agent,
-    completion::{CompletionModel, PromptError},
+    completion::CompletionModel,
     message::{AssistantContent, Message, Text, ToolResultContent, UserContent},
     streaming::StreamingCompletion,
     OneOrMany,
@@ -11,16 +11,30 @@ use std::pin::Pin;
 
 use anyhow::Result;
 
-use super::util::confirm_tool_call;
+use crate::{
+    client::{
+        enums::StopReason, error::ChatError, response::Usage, traits::StreamingResponseExt,
+        Citation, ToolCallRecord,
+    },
+    tools::BuiltinTools,
+};
+
+use super::util::approve_tool_call;
 
 pub type StreamingContentResult =
-    Pin<Box<dyn Stream<Item = Result<StreamingContent, PromptError>> + Send>>;
+    Pin<Box<dyn Stream<Item = Result<StreamingContent, ChatError>> + Send>>;
 
 #[derive(Debug)]
 pub enum StreamingContent {
     Text(String),
     PauseSpinner,
     StartSpinner,
+    ToolCall(ToolCallRecord),
+    /// Sources of a search answer. The answer follows in one `Text`, so the
+    /// citation ranges match it.
+    Citations(Vec<Citation>),
+    /// Last item of the stream
+    Done(StopReason, Usage),
 }
 
 pub struct StreamingMultiTurnAgent;
@@ -30,10 +44,11 @@ impl StreamingMultiTurnAgent {
         prompt: impl Into<Message> + Send,
         agent: Agent<M>,
         mut chat_history: Vec<Message>,
+        tools: BuiltinTools,
     ) -> StreamingContentResult
     where
         M: CompletionModel + 'static,
-        <M as CompletionModel>::StreamingResponse: std::marker::Send,
+        <M as CompletionModel>::StreamingResponse: std::marker::Send + StreamingResponseExt,
     {
         let prompt: Message = prompt.into();
 
@@ -42,8 +57,9 @@ impl StreamingMultiTurnAgent {
             let mut did_write_message = false;
             let mut did_call_tool = false;
             let mut finish = false;
+            let mut usage = Usage::default();
 
-            'outer: loop {
+            let stop_reason = 'outer: loop {
                 let mut stream = agent
                     .stream_completion(current_prompt.to_owned(), chat_history.to_owned())
                     .await?
@@ -76,28 +92,43 @@ impl StreamingMultiTurnAgent {
                             finish = false;
 
                             yield Ok(StreamingContent::PauseSpinner);
-                            let confirmation = confirm_tool_call(&tool_call.function.name, None);
+                            let approval = approve_tool_call(&tools, &tool_call.function.name, &tool_call.function.arguments, None);
                             yield Ok(StreamingContent::StartSpinner);
 
-                            let tool_result = if confirmation {
-                                match agent.tools.call(&tool_call.function.name, tool_call.function.arguments.to_string()).await {
+                            let tool_result = match approval {
+                                Ok(()) => match agent.tools.call(&tool_call.function.name, tool_call.function.arguments.to_string()).await {
                                     Ok(res) => res,
                                     Err(e) => e.to_string(),
-                                }
-                            } else {
-                                "Cancelled by user".to_string()
+                                },
+                                Err(reason) => reason,
                             };
 
+                            yield Ok(StreamingContent::ToolCall(ToolCallRecord::new(
+                                &tool_call.function.name,
+                                &tool_call.function.arguments,
+                                &tool_result,
+                            )));
+
                             let tool_call_msg = AssistantContent::ToolCall(tool_call.to_owned());
                             tool_calls.push(tool_call_msg);
                             tool_results.push((tool_call.id, tool_result));
                         }
-                        Err(_) => {
-                            break 'outer;
+                        // What was streamed so far stays with the caller
+                        Err(e) => {
+                            yield Err(e.into());
+                            return;
                         }
                     }
                 }
 
+                let stop_reason = match &stream.response {
+                    Some(response) => {
+                        usage += response.usage();
+                        response.stop_reason(agent.max_tokens)
+                    }
+                    None => StopReason::None,
+                };
+
                 // Add tool calls to chat history
                 if !tool_calls.is_empty() {
                     chat_history.push(Message::Assistant {
@@ -118,9 +149,11 @@ impl StreamingMultiTurnAgent {
                 current_prompt = chat_history.pop().unwrap();
 
                 if finish {
-                    break 'outer;
+                    break 'outer stop_reason;
                 }
-            }
+            };
+
+            yield Ok(StreamingContent::Done(stop_reason, usage));
         })) as _
     }
 }
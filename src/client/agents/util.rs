@@ -1,10 +1,31 @@
-use std::time::Duration;
+use std::{io, time::Duration};
 
 use indicatif::ProgressBar;
 
-use crate::utils::console::get_select_theme;
+use crate::{
+    tools::{Approval, BuiltinTools},
+    utils::{console::get_select_theme, interrupt},
+};
 
-pub fn confirm_tool_call(name: &str, spinner: Option<&ProgressBar>) -> bool {
+/// Decide whether a tool call may run. The error is the tool result sent
+/// back to the model instead.
+pub fn approve_tool_call(
+    tools: &BuiltinTools,
+    name: &str,
+    arguments: &serde_json::Value,
+    spinner: Option<&ProgressBar>,
+) -> Result<(), String> {
+    match tools.approval(name, arguments) {
+        Approval::Allow => Ok(()),
+        Approval::Deny(reason) => Err(reason),
+        Approval::Ask(prompt) => match confirm_tool_call(&prompt, spinner) {
+            true => Ok(()),
+            false => Err("Cancelled by user".to_string()),
+        },
+    }
+}
+
+pub fn confirm_tool_call(prompt: &str, spinner: Option<&ProgressBar>) -> bool {
     if let Some(spinner) = spinner {
         spinner.disable_steady_tick();
         let _ = dialoguer::console::Term::stdout().clear_last_lines(2);
@@ -12,13 +33,21 @@ pub fn confirm_tool_call(name: &str, spinner: Option<&ProgressBar>) -> bool {
     }
 
     let confirmation = dialoguer::Select::with_theme(&get_select_theme())
-        .with_prompt(format!("Run tool '{name}'?"))
+        .with_prompt(prompt)
         .default(0)
         .items(&["Yes", "No"])
         .clear(true)
-        .interact()
-        .unwrap_or(0)
-        == 0;
+        .interact();
+
+    // Ctrl-C at the prompt is read as a key, it cancels the whole generation
+    let confirmation = match confirmation {
+        Ok(selection) => selection == 0,
+        Err(dialoguer::Error::IO(e)) if e.kind() == io::ErrorKind::Interrupted => {
+            interrupt::interrupt();
+            false
+        }
+        Err(_) => false,
+    };
 
     if let Some(spinner) = spinner {
         spinner.enable_steady_tick(Duration::from_millis(100));
@@ -1,7 +1,12 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum StopReason {
     Stop,
     Length,
     ContentFilter,
     ToolCall,
+    #[default]
     None,
 }
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use rig::completion::{CompletionError, PromptError};
+use serde::Serialize;
+
+/// What went wrong with a request, so the user can be told what to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Authentication,
+    RateLimit,
+    ContextLength,
+    ModelNotFound,
+    Network,
+    ContentFilter,
+    Tool,
+    Other,
+}
+
+/// A failed request to the model
+#[derive(Debug, Clone)]
+pub struct ChatError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl ChatError {
+    pub fn new(kind: ErrorKind, message: &str) -> Self {
+        Self {
+            kind,
+            message: message.trim().to_string(),
+        }
+    }
+
+    /// What the user can do about it
+    pub fn hint(&self) -> Option<&'static str> {
+        match self.kind {
+            ErrorKind::Authentication => {
+                Some("Check the API key of the provider. Run `termai options` to update your key.")
+            }
+            ErrorKind::RateLimit => Some(
+                "The provider is rate limiting requests or your quota is used up. Wait a moment and try again, or check your plan.",
+            ),
+            ErrorKind::ContextLength => Some(
+                "The conversation is too long for the model. Use /compact or /clear in chat, send less text or pick a model with a larger context window.",
+            ),
+            ErrorKind::ModelNotFound => Some(
+                "The provider doesn't know the model. Pick another one with /model in chat or `termai model set <id>`.",
+            ),
+            ErrorKind::Network => {
+                Some("Check your internet connection, proxy and the provider's base URL.")
+            }
+            ErrorKind::ContentFilter => {
+                Some("The provider's content filter blocked the request. Try rephrasing it.")
+            }
+            ErrorKind::Tool => Some("A tool failed. Use /tools to check the MCP servers."),
+            ErrorKind::Other => None,
+        }
+    }
+
+    /// The message followed by the hint, for plain text output
+    pub fn with_hint(&self) -> String {
+        match self.hint() {
+            Some(hint) => format!("{}. {hint}", self.message.trim_end_matches('.')),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<PromptError> for ChatError {
+    fn from(error: PromptError) -> Self {
+        match error {
+            PromptError::CompletionError(error) => error.into(),
+            PromptError::ToolError(error) => Self::new(ErrorKind::Tool, &error.to_string()),
+            PromptError::MaxDepthError { max_depth, .. } => Self::new(
+                ErrorKind::Other,
+                &format!("The model called tools {max_depth} times in a row without answering"),
+            ),
+        }
+    }
+}
+
+impl From<CompletionError> for ChatError {
+    fn from(error: CompletionError) -> Self {
+        match error {
+            CompletionError::HttpError(error) => match error.status() {
+                Some(status) => classify(&error.to_string(), Some(status.as_u16())),
+                None => Self::new(ErrorKind::Network, &error.to_string()),
+            },
+            CompletionError::ProviderError(body) => {
+                // Streaming errors start with the HTTP status, e.g. `429 Too Many Requests: {...}`
+                let status = body
+                    .split_once(' ')
+                    .and_then(|(status, _)| status.parse::<u16>().ok());
+                classify(&body, status)
+            }
+            error => classify(&error.to_string(), None),
+        }
+    }
+}
+
+/// Errors are told apart by the HTTP status and the codes and phrases that
+/// OpenAI and Anthropic put in their error bodies
+fn classify(text: &str, status: Option<u16>) -> ChatError {
+    let lower = text.to_lowercase();
+    let has = |phrases: &[&str]| phrases.iter().any(|phrase| lower.contains(phrase));
+
+    let kind = if has(&[
+        "content_filter",
+        "content filter",
+        "content_policy",
+        "content policy",
+    ]) {
+        ErrorKind::ContentFilter
+    } else if has(&[
+        "context_length",
+        "context length",
+        "context window",
+        "maximum context",
+        "prompt is too long",
+        "too many tokens",
+    ]) {
+        ErrorKind::ContextLength
+    } else if matches!(status, Some(429 | 529))
+        || has(&[
+            "rate limit",
+            "rate_limit",
+            "quota",
+            "overloaded",
+            "credit balance",
+        ])
+    {
+        ErrorKind::RateLimit
+    } else if matches!(status, Some(401 | 403))
+        || has(&[
+            "authentication",
+            "invalid_api_key",
+            "api key",
+            "x-api-key",
+            "permission_error",
+            "unauthorized",
+        ])
+    {
+        ErrorKind::Authentication
+    } else if status == Some(404) || has(&["model_not_found", "not_found_error", "does not exist"])
+    {
+        ErrorKind::ModelNotFound
+    } else if has(&[
+        "error sending request",
+        "connection",
+        "timed out",
+        "dns error",
+    ]) {
+        ErrorKind::Network
+    } else {
+        ErrorKind::Other
+    };
+
+    ChatError::new(kind, &readable(text))
+}
+
+/// The message of a JSON error body, or the text as is
+fn readable(text: &str) -> String {
+    let Some(start) = text.find('{') else {
+        return text.to_string();
+    };
+    let Ok(body) = serde_json::from_str::<serde_json::Value>(&text[start..]) else {
+        return text.to_string();
+    };
+
+    body.pointer("/error/message")
+        .or_else(|| body.get("message"))
+        .and_then(|message| message.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| text.to_string())
+}
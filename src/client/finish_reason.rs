@@ -1,6 +1,11 @@
 use rig::providers::{anthropic, openai};
 
-use super::{enums::StopReason, traits::CompetionResponseExt};
+use super::{
+    enums::StopReason,
+    models::openai::SearchResponse,
+    response::Usage,
+    traits::{CompetionResponseExt, StreamingResponseExt},
+};
 
 impl CompetionResponseExt for anthropic::completion::CompletionResponse {
     fn stop_reason(&self) -> StopReason {
@@ -16,6 +21,13 @@ impl CompetionResponseExt for anthropic::completion::CompletionResponse {
             _ => StopReason::None,
         }
     }
+
+    fn usage(&self) -> Usage {
+        Usage {
+            input_tokens: self.usage.input_tokens,
+            output_tokens: self.usage.output_tokens,
+        }
+    }
 }
 
 impl CompetionResponseExt for openai::CompletionResponse {
@@ -40,4 +52,51 @@ impl CompetionResponseExt for openai::CompletionResponse {
             _ => StopReason::None,
         }
     }
+
+    fn usage(&self) -> Usage {
+        self.usage.as_ref().map(openai_usage).unwrap_or_default()
+    }
+}
+
+impl CompetionResponseExt for SearchResponse {
+    fn stop_reason(&self) -> StopReason {
+        let finish_reason = self
+            .choices
+            .first()
+            .and_then(|choice| choice.finish_reason.as_deref());
+
+        match finish_reason {
+            Some("stop") => StopReason::Stop,
+            Some("length") => StopReason::Length,
+            Some("content_filter") => StopReason::ContentFilter,
+            _ => StopReason::None,
+        }
+    }
+
+    fn usage(&self) -> Usage {
+        self.usage.as_ref().map(openai_usage).unwrap_or_default()
+    }
+}
+
+impl StreamingResponseExt for anthropic::streaming::StreamingCompletionResponse {
+    fn usage(&self) -> Usage {
+        Usage {
+            input_tokens: self.usage.input_tokens.unwrap_or_default() as u64,
+            output_tokens: self.usage.output_tokens as u64,
+        }
+    }
+}
+
+impl StreamingResponseExt for openai::StreamingCompletionResponse {
+    fn usage(&self) -> Usage {
+        openai_usage(&self.usage)
+    }
+}
+
+/// OpenAI reports the prompt and total tokens
+fn openai_usage(usage: &openai::Usage) -> Usage {
+    Usage {
+        input_tokens: usage.prompt_tokens as u64,
+        output_tokens: usage.total_tokens.saturating_sub(usage.prompt_tokens) as u64,
+    }
 }
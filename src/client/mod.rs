@@ -1,8 +1,10 @@
 mod agents;
 mod constants;
 mod enums;
+mod error;
 mod finish_reason;
 mod models;
+mod response;
 mod streaming;
 mod traits;
 
@@ -11,28 +13,36 @@ use reqwest::{Client as Reqwest, RequestBuilder};
 use rig::{
     agent::{Agent, AgentBuilder},
     client::CompletionClient,
-    completion::{CompletionModel, PromptError},
+    completion::{CompletionError, CompletionModel},
     message::Message,
 };
 use serde::de::DeserializeOwned;
+use serde_json::json;
 
-use crate::{mcp::McpClient, provider::Provider, utils::shell::detect_shell_environment};
+use crate::{
+    mcp::McpClient, provider::Provider, tools::BuiltinTools, utils::shell::detect_shell_environment,
+};
 
 use agents::{MultiTurnAgent, StreamingMultiTurnAgent};
-use constants::{CHAT_PREAMBLE, EXPLAIN_PREAMBLE, SUGGEST_PREAMBLE};
+use constants::{CHAT_PREAMBLE, EXPLAIN_PREAMBLE, SUGGEST_PREAMBLE, SUMMARY_PREAMBLE};
 use models::{anthropic, openai};
+use traits::CompetionResponseExt;
 use traits::ModelTrait;
 
 pub use agents::{StreamingContent, StreamingContentResult};
+pub use enums::StopReason;
+pub use error::{ChatError, ErrorKind};
+pub use response::{ChatResponse, Citation, ToolCallRecord, Usage};
 
 pub struct Client;
 
 impl Client {
     pub async fn chat_stream(
-        prompt: &str,
+        prompt: Message,
         messages: Vec<Message>,
         provider: &Provider,
         mcp_clients: &mut Vec<McpClient>,
+        tools: &BuiltinTools,
         search: bool,
     ) -> StreamingContentResult {
         let stream = match provider {
@@ -50,22 +60,49 @@ impl Client {
                     .max_tokens(get_max_tokens(&model))
                     .preamble(CHAT_PREAMBLE);
 
-                let agent = Self::build_agent(agent_builder, Some(mcp_clients), search).await;
+                let agent =
+                    Self::build_agent(agent_builder, Some(mcp_clients), tools, search).await;
 
-                StreamingMultiTurnAgent::multi_turn_prompt(prompt, agent, messages.clone()).await
+                StreamingMultiTurnAgent::multi_turn_prompt(
+                    prompt,
+                    agent,
+                    messages.clone(),
+                    tools.clone(),
+                )
+                .await
             }
             Provider::OpenAI(settings) => {
-                let (_, api_key, cm, sm) = settings.get();
+                let (base_url, api_key, cm, sm) = settings.get();
 
                 let model = choose_model(search, &cm, &sm);
 
+                if search {
+                    let response = Self::openai_search(
+                        prompt,
+                        CHAT_PREAMBLE,
+                        messages,
+                        &base_url,
+                        &api_key,
+                        &model,
+                    )
+                    .await;
+                    return search_stream(response);
+                }
+
                 let agent_builder = rig::providers::openai::Client::new(&api_key)
                     .agent(&model)
                     .preamble(CHAT_PREAMBLE);
 
-                let agent = Self::build_agent(agent_builder, Some(mcp_clients), search).await;
+                let agent =
+                    Self::build_agent(agent_builder, Some(mcp_clients), tools, search).await;
 
-                StreamingMultiTurnAgent::multi_turn_prompt(prompt, agent, messages.clone()).await
+                StreamingMultiTurnAgent::multi_turn_prompt(
+                    prompt,
+                    agent,
+                    messages.clone(),
+                    tools.clone(),
+                )
+                .await
             }
         };
 
@@ -73,26 +110,27 @@ impl Client {
     }
 
     pub async fn chat(
-        prompt: &str,
+        prompt: Message,
         messages: Vec<Message>,
         provider: &Provider,
         mcp_clients: &mut Vec<McpClient>,
+        tools: &BuiltinTools,
         spinner: &ProgressBar,
         search: bool,
-    ) -> Result<String, PromptError> {
+    ) -> Result<ChatResponse, ChatError> {
         Self::chat_completion(
             prompt,
             CHAT_PREAMBLE,
             messages,
             provider,
-            Some(mcp_clients),
+            Some((mcp_clients, tools)),
             Some(spinner),
             search,
         )
         .await
     }
 
-    pub async fn suggest(prompt: &str, provider: &Provider) -> Result<String, PromptError> {
+    pub async fn suggest(prompt: &str, provider: &Provider) -> Result<ChatResponse, ChatError> {
         Self::chat_completion(
             prompt,
             SUGGEST_PREAMBLE,
@@ -109,7 +147,7 @@ impl Client {
         prompt: &str,
         command_to_revise: &str,
         provider: &Provider,
-    ) -> Result<String, PromptError> {
+    ) -> Result<String, ChatError> {
         let messages = vec![Message::assistant(command_to_revise)];
         Self::chat_completion(
             prompt,
@@ -121,9 +159,10 @@ impl Client {
             false,
         )
         .await
+        .map(|response| response.text)
     }
 
-    pub async fn explain(prompt: &str, provider: &Provider) -> Result<String, PromptError> {
+    pub async fn explain(prompt: &str, provider: &Provider) -> Result<ChatResponse, ChatError> {
         Self::chat_completion(
             prompt,
             EXPLAIN_PREAMBLE,
@@ -136,6 +175,20 @@ impl Client {
         .await
     }
 
+    pub async fn summarize(transcript: &str, provider: &Provider) -> Result<String, ChatError> {
+        Self::chat_completion(
+            transcript,
+            SUMMARY_PREAMBLE,
+            vec![],
+            provider,
+            None,
+            None,
+            false,
+        )
+        .await
+        .map(|response| response.text)
+    }
+
     pub async fn fetch_models(provider: &Provider) -> Vec<(String, String)> {
         let req = Self::build_models_request(provider);
 
@@ -156,21 +209,25 @@ impl Client {
     }
 
     async fn chat_completion(
-        prompt: &str,
+        prompt: impl Into<Message> + Send,
         preamble: &str,
         messages: Vec<Message>,
         provider: &Provider,
-        mcp_clients: Option<&mut Vec<McpClient>>,
+        tools: Option<(&mut Vec<McpClient>, &BuiltinTools)>,
         spinner: Option<&ProgressBar>,
         search: bool,
-    ) -> Result<String, PromptError> {
+    ) -> Result<ChatResponse, ChatError> {
+        let (mcp_clients, builtin_tools) = match tools {
+            Some((mcp_clients, builtin_tools)) => (Some(mcp_clients), builtin_tools.clone()),
+            None => (None, BuiltinTools::default()),
+        };
         let mut preamble = preamble.to_string();
 
         if let Some((shell, os)) = detect_shell_environment() {
             preamble += format!("\n\nActive Shell: {} on {}", shell, os).as_str();
         }
 
-        match provider {
+        let response = match provider {
             Provider::Anthropic(settings) => {
                 let (_, api_key, cm, sm) = settings.get();
 
@@ -183,44 +240,116 @@ impl Client {
                     .max_tokens(get_max_tokens(&model))
                     .preamble(&preamble);
 
-                let agent = Self::build_agent(agent_builder, mcp_clients, search).await;
+                let agent =
+                    Self::build_agent(agent_builder, mcp_clients, &builtin_tools, search).await;
 
-                let mut agent = MultiTurnAgent::new(agent, messages.clone());
+                let mut agent = MultiTurnAgent::new(agent, messages.clone(), builtin_tools);
 
                 agent.multi_turn_prompt(prompt, spinner).await
             }
             Provider::OpenAI(settings) => {
-                let (_, api_key, cm, sm) = settings.get();
+                let (base_url, api_key, cm, sm) = settings.get();
 
                 let model = choose_model(search, &cm, &sm);
 
+                if search {
+                    return Self::openai_search(
+                        prompt.into(),
+                        &preamble,
+                        messages,
+                        &base_url,
+                        &api_key,
+                        &model,
+                    )
+                    .await;
+                }
+
                 let agent_builder = rig::providers::openai::Client::new(&api_key)
                     .agent(&model)
                     .preamble(&preamble);
 
-                let agent = Self::build_agent(agent_builder, mcp_clients, search).await;
+                let agent =
+                    Self::build_agent(agent_builder, mcp_clients, &builtin_tools, search).await;
 
-                let mut agent = MultiTurnAgent::new(agent, messages.clone());
+                let mut agent = MultiTurnAgent::new(agent, messages.clone(), builtin_tools);
 
                 agent.multi_turn_prompt(prompt, spinner).await
             }
+        };
+
+        response
+            .map_err(ChatError::from)
+            .map(|response| ChatResponse {
+                model: choose_model(
+                    search,
+                    &provider.completion_model(),
+                    &provider.search_model().unwrap_or_default(),
+                ),
+                ..response
+            })
+    }
+
+    /// OpenAI search models answer with URL citations, which rig drops, so
+    /// they are asked directly. Search models take no tools.
+    async fn openai_search(
+        prompt: Message,
+        preamble: &str,
+        messages: Vec<Message>,
+        base_url: &str,
+        api_key: &str,
+        model: &str,
+    ) -> Result<ChatResponse, ChatError> {
+        let mut history = vec![rig::providers::openai::Message::system(preamble)];
+        for message in messages.into_iter().chain([prompt]) {
+            let converted: Vec<rig::providers::openai::Message> =
+                message.try_into().map_err(CompletionError::from)?;
+            history.extend(converted);
         }
+
+        let response = Reqwest::new()
+            .post(format!("{base_url}/v1/chat/completions"))
+            .bearer_auth(api_key)
+            .json(&json!({ "model": model, "messages": history }))
+            .send()
+            .await
+            .map_err(CompletionError::from)?
+            .json::<openai::SearchResponse>()
+            .await
+            .map_err(CompletionError::from)?;
+
+        if let Some(error) = response.error {
+            return Err(CompletionError::ProviderError(error.message).into());
+        }
+
+        Ok(ChatResponse {
+            text: response.text(),
+            model: model.to_string(),
+            stop_reason: response.stop_reason(),
+            usage: response.usage(),
+            citations: response.citations(),
+            ..Default::default()
+        })
     }
 
     async fn build_agent<M: CompletionModel>(
         mut agent_builder: AgentBuilder<M>,
         mcp_clients: Option<&mut Vec<McpClient>>,
+        builtin_tools: &BuiltinTools,
         skip_tools: bool,
     ) -> Agent<M> {
         if skip_tools {
             return agent_builder.build();
         }
 
+        agent_builder = builtin_tools.add_tools(agent_builder);
+
         if let Some(clients) = mcp_clients {
             for client in clients {
                 // Add tool from MCP client if enabled and initialized
                 if client.is_enabled() && client.initialize().await.is_ok() {
-                    agent_builder = client.add_tools(agent_builder).await;
+                    agent_builder = client
+                        .add_tools(agent_builder, &builtin_tools.names())
+                        .await;
                 }
             }
 
@@ -272,6 +401,18 @@ impl Client {
     }
 }
 
+/// A search answer arrives in one piece, after its sources
+fn search_stream(response: Result<ChatResponse, ChatError>) -> StreamingContentResult {
+    Box::pin(async_stream::stream! {
+        let response = response?;
+        if !response.citations.is_empty() {
+            yield Ok(StreamingContent::Citations(response.citations));
+        }
+        yield Ok(StreamingContent::Text(response.text));
+        yield Ok(StreamingContent::Done(response.stop_reason, response.usage));
+    })
+}
+
 fn choose_model(search: bool, completion_model: &str, search_model: &str) -> String {
     if search {
         search_model.to_string()
@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use crate::client::traits::ModelTrait;
+use crate::client::{response::Citation, traits::ModelTrait};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ModelResponse {
@@ -32,3 +32,77 @@ impl ModelTrait for ModelResponse {
         }
     }
 }
+
+/// Chat completion of a search model. rig drops the URL citations, so search
+/// requests are sent without it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchResponse {
+    #[serde(default)]
+    pub choices: Vec<SearchChoice>,
+    pub usage: Option<rig::providers::openai::Usage>,
+    pub error: Option<ModelErrorObject>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchChoice {
+    pub message: SearchMessage,
+    pub finish_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchMessage {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Annotation {
+    UrlCitation {
+        url_citation: UrlCitation,
+    },
+    #[serde(other)]
+    Other,
+}
+
+/// `start_index` and `end_index` count characters of the message content
+#[derive(Debug, Clone, Deserialize)]
+pub struct UrlCitation {
+    pub start_index: usize,
+    pub end_index: usize,
+    pub url: String,
+    #[serde(default)]
+    pub title: String,
+}
+
+/// Only one choice is requested
+impl SearchResponse {
+    pub fn text(&self) -> String {
+        self.choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .unwrap_or_default()
+    }
+
+    /// Citations of `text`. Empty ranges and ranges past the end of the
+    /// text are left out.
+    pub fn citations(&self) -> Vec<Citation> {
+        let len = self.text().chars().count();
+        self.choices
+            .first()
+            .into_iter()
+            .flat_map(|choice| &choice.message.annotations)
+            .filter_map(|annotation| match annotation {
+                Annotation::UrlCitation { url_citation } => Some(Citation {
+                    title: url_citation.title.clone(),
+                    url: url_citation.url.clone(),
+                    start: url_citation.start_index,
+                    end: url_citation.end_index,
+                }),
+                Annotation::Other => None,
+            })
+            .filter(|citation| citation.start < citation.end && citation.end <= len)
+            .collect()
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::enums::StopReason;
+
+/// A tool call made by the model while answering, together with its result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub name: String,
+    pub arguments: serde_json::Value,
+    pub result: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ToolCallRecord {
+    pub fn new(name: &str, arguments: &serde_json::Value, result: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            arguments: arguments.to_owned(),
+            result: result.to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// A web page a search answer cites. `start` and `end` are the character
+/// range of the answer text it backs up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Citation {
+    pub title: String,
+    pub url: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Tokens used by a request, summed over every turn of a tool calling loop
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl std::ops::AddAssign for Usage {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChatResponse {
+    pub text: String,
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Model that produced the answer
+    pub model: String,
+    pub stop_reason: StopReason,
+    pub usage: Usage,
+    /// Sources of a search answer, empty for other answers
+    pub citations: Vec<Citation>,
+}
@@ -1,8 +1,23 @@
-use super::enums::StopReason;
+use super::{enums::StopReason, response::Usage};
 
 pub trait ModelTrait {
     fn extract_models(&self) -> Result<Vec<(String, String)>, String>;
 }
 pub trait CompetionResponseExt {
     fn stop_reason(&self) -> StopReason;
+    fn usage(&self) -> Usage;
+}
+
+/// Final message of a stream, which only carries the token usage
+pub trait StreamingResponseExt {
+    fn usage(&self) -> Usage;
+
+    /// The stream doesn't say why it stopped, so an answer that used up
+    /// `max_tokens` is taken as cut off
+    fn stop_reason(&self, max_tokens: Option<u64>) -> StopReason {
+        match max_tokens {
+            Some(max) if self.usage().output_tokens >= max => StopReason::Length,
+            _ => StopReason::Stop,
+        }
+    }
 }
@@ -1,12 +1,20 @@
 use console::style;
 use serde::{Deserialize, Serialize};
 
+use std::{path::PathBuf, time::Duration};
+
 use crate::{
     mcp::McpClient,
     provider::{llm_models, Provider},
+    tools::{fs::Scope, shell::ShellTool, BuiltinTools},
     utils::enums::ProviderName,
 };
 
+const DEFAULT_COMPACT_THRESHOLD: u8 = 80;
+const DEFAULT_STDIN_LIMIT_KB: u32 = 256;
+const DEFAULT_SHELL_TIMEOUT_SECS: u32 = 30;
+const DEFAULT_SHELL_OUTPUT_LIMIT_KB: u32 = 32;
+
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     providers: Vec<Provider>,
@@ -23,6 +31,32 @@ pub struct Config {
     available_search_models: Vec<(ProviderName, String, String)>,
     #[serde(default)]
     mcp_clients: Vec<McpClient>,
+    /// Percentage of the context window at which chat history is compacted (0 disables it)
+    #[serde(default)]
+    compact_threshold: Option<u8>,
+    /// Maximum amount of piped stdin that is attached to a prompt, in KB
+    #[serde(default)]
+    stdin_limit_kb: Option<u32>,
+    /// Let the chat model run shell commands (enabled unless set to false)
+    #[serde(default)]
+    shell_tool: Option<bool>,
+    #[serde(default)]
+    shell_timeout_secs: Option<u32>,
+    /// Maximum amount of stdout and stderr returned to the model, in KB each
+    #[serde(default)]
+    shell_output_limit_kb: Option<u32>,
+    /// Glob patterns of shell commands that run without asking
+    #[serde(default)]
+    shell_allow: Vec<String>,
+    /// Glob patterns of shell commands that are always refused
+    #[serde(default)]
+    shell_deny: Vec<String>,
+    /// Let the chat model read files (enabled unless set to false)
+    #[serde(default)]
+    fs_tools: Option<bool>,
+    /// Directories the filesystem tools may read, the working directory if empty
+    #[serde(default)]
+    fs_roots: Vec<PathBuf>,
 }
 
 impl Config {
@@ -52,6 +86,113 @@ impl Config {
         self.save();
     }
 
+    pub fn set_streaming(&mut self, enabled: bool) {
+        self.use_streaming = enabled;
+        self.save();
+    }
+
+    pub fn compact_threshold(&self) -> u8 {
+        self.compact_threshold.unwrap_or(DEFAULT_COMPACT_THRESHOLD)
+    }
+
+    pub fn set_compact_threshold(&mut self, percent: u8) {
+        self.compact_threshold = Some(percent);
+        self.save();
+    }
+
+    pub fn stdin_limit_kb(&self) -> u32 {
+        self.stdin_limit_kb.unwrap_or(DEFAULT_STDIN_LIMIT_KB)
+    }
+
+    pub fn set_stdin_limit_kb(&mut self, limit: u32) {
+        self.stdin_limit_kb = Some(limit);
+        self.save();
+    }
+
+    pub fn shell_tool(&self) -> bool {
+        self.shell_tool.unwrap_or(true)
+    }
+
+    pub fn set_shell_tool(&mut self, enabled: bool) {
+        self.shell_tool = Some(enabled);
+        self.save();
+    }
+
+    pub fn shell_timeout_secs(&self) -> u32 {
+        self.shell_timeout_secs
+            .unwrap_or(DEFAULT_SHELL_TIMEOUT_SECS)
+    }
+
+    pub fn set_shell_timeout_secs(&mut self, secs: u32) {
+        self.shell_timeout_secs = Some(secs);
+        self.save();
+    }
+
+    pub fn shell_output_limit_kb(&self) -> u32 {
+        self.shell_output_limit_kb
+            .unwrap_or(DEFAULT_SHELL_OUTPUT_LIMIT_KB)
+    }
+
+    pub fn set_shell_output_limit_kb(&mut self, limit: u32) {
+        self.shell_output_limit_kb = Some(limit);
+        self.save();
+    }
+
+    pub fn shell_allow(&self) -> &[String] {
+        &self.shell_allow
+    }
+
+    pub fn set_shell_allow(&mut self, patterns: Vec<String>) {
+        self.shell_allow = patterns;
+        self.save();
+    }
+
+    pub fn shell_deny(&self) -> &[String] {
+        &self.shell_deny
+    }
+
+    pub fn set_shell_deny(&mut self, patterns: Vec<String>) {
+        self.shell_deny = patterns;
+        self.save();
+    }
+
+    pub fn fs_tools(&self) -> bool {
+        self.fs_tools.unwrap_or(true)
+    }
+
+    pub fn set_fs_tools(&mut self, enabled: bool) {
+        self.fs_tools = Some(enabled);
+        self.save();
+    }
+
+    pub fn fs_roots(&self) -> &[PathBuf] {
+        &self.fs_roots
+    }
+
+    pub fn set_fs_roots(&mut self, roots: Vec<PathBuf>) {
+        self.fs_roots = roots;
+        self.save();
+    }
+
+    /// Built-in tools for the chat agent, as configured
+    pub fn builtin_tools(&self) -> BuiltinTools {
+        let shell = self.shell_tool().then(|| {
+            ShellTool::new(
+                Duration::from_secs(self.shell_timeout_secs() as u64),
+                self.shell_output_limit_kb() as usize * 1024,
+                &self.shell_allow,
+                &self.shell_deny,
+            )
+        });
+
+        let fs = self.fs_tools().then(|| match self.fs_roots.is_empty() {
+            true => Scope::new(&[PathBuf::from(".")]),
+            false => Scope::new(&self.fs_roots),
+        });
+
+        BuiltinTools { shell, fs }
+    }
+
     pub fn is_configured(&self, provider_name: ProviderName) -> bool {
         self.providers.iter().any(|p| p.name() == provider_name)
     }
@@ -224,6 +365,26 @@ impl Config {
         &mut self.mcp_clients
     }
 
+    pub fn mcp_client_names(&self) -> Vec<String> {
+        self.mcp_clients.iter().map(|c| c.name()).collect()
+    }
+
+    pub fn find_mcp_client_mut(&mut self, name: &str) -> Option<&mut McpClient> {
+        self.mcp_clients.iter_mut().find(|c| c.name() == name)
+    }
+
+    pub fn remove_mcp_client(&mut self, name: &str) -> bool {
+        let count = self.mcp_clients.len();
+        self.mcp_clients.retain(|c| c.name() != name);
+
+        let removed = self.mcp_clients.len() != count;
+        if removed {
+            self.save();
+        }
+
+        removed
+    }
+
     pub fn add_mcp_client(&mut self, mcp_client: McpClient) {
         self.mcp_clients.push(mcp_client);
         self.save();
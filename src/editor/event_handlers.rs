@@ -1,10 +1,27 @@
-use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, KeyEvent, RepeatCount};
+use std::sync::{Arc, Mutex};
 
-pub struct TabEventHandler;
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, Movement, RepeatCount};
 
-impl ConditionalEventHandler for TabEventHandler {
-    fn handle(&self, evt: &Event, n: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
-        debug_assert_eq!(*evt, Event::from(KeyEvent::from('\t')));
-        ctx.hint_text().map(|hint| Cmd::Insert(n, hint.to_owned()))
+/// Ctrl-C clears the line, and interrupts the editor at an empty prompt
+pub struct InterruptEventHandler;
+
+impl ConditionalEventHandler for InterruptEventHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        match ctx.line().is_empty() {
+            true => Some(Cmd::Interrupt),
+            false => Some(Cmd::Kill(Movement::WholeBuffer)),
+        }
+    }
+}
+
+/// Ctrl-X Ctrl-E keeps the buffer for the external editor and ends the line
+pub struct ComposeEventHandler(pub Arc<Mutex<Option<String>>>);
+
+impl ConditionalEventHandler for ComposeEventHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        if let Ok(mut buffer) = self.0.lock() {
+            *buffer = Some(ctx.line().to_string());
+        }
+        Some(Cmd::AcceptLine)
     }
 }
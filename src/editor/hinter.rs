@@ -1,13 +1,15 @@
 use std::borrow::Cow::{self, Borrowed, Owned};
+use std::fs;
 
 use derivative::Derivative;
 use radix_trie::{Trie, TrieCommon};
+use rustyline::completion::{longest_common_prefix, Completer};
 use rustyline::highlight::Highlighter;
 use rustyline::hint::{Hint, Hinter as ReadlineHinter};
 use rustyline::Context;
-use rustyline::{Completer, Helper, Validator};
+use rustyline::{Helper, Validator};
 
-#[derive(Completer, Helper, Validator)]
+#[derive(Helper, Validator)]
 pub struct Hinter {
     hints: Trie<String, CommandHint>,
 }
@@ -31,6 +33,51 @@ impl Hinter {
     }
 }
 
+impl Hinter {
+    /// Where the word being completed starts in `line`, and what it can become
+    fn candidates(&self, line: &str) -> Option<(usize, Vec<String>)> {
+        // File mentions can appear anywhere in the line
+        let word = line.rsplit(char::is_whitespace).next()?;
+        if let Some(typed) = word.strip_prefix('@') {
+            let (offset, candidates) = complete_paths(typed);
+            return Some((line.len() - typed.len() + offset, candidates));
+        }
+
+        let Some((command, _)) = line.split_once(char::is_whitespace) else {
+            let candidates = self
+                .hints
+                .get_raw_descendant(line)?
+                .keys()
+                .filter(|command| command.starts_with(line))
+                .cloned()
+                .collect();
+            return Some((0, candidates));
+        };
+
+        let hint = self
+            .hints
+            .get(command)
+            .or_else(|| self.hints.get(&format!("{command} ")))?;
+        let complete_args = hint.args.as_ref()?;
+        let args = line[command.len()..].trim_start();
+        let (offset, candidates) = complete_args(args);
+        Some((line.len() - args.len() + offset, candidates))
+    }
+}
+
+impl Completer for Hinter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(&line[..pos]).unwrap_or((pos, vec![])))
+    }
+}
+
 impl ReadlineHinter for Hinter {
     type Hint = CommandHint;
 
@@ -39,16 +86,67 @@ impl ReadlineHinter for Hinter {
             return None;
         }
 
-        let prefix = line;
-        self.hints.get_raw_descendant(prefix).and_then(|node| {
-            if let Some(hint) = node.value() {
-                Some(hint.suffix(pos))
-            } else {
-                // If the node doesn't have a value, find the first child with a value
-                node.iter().map(|(_, hint)| hint.suffix(pos)).next()
+        // Hint as far as all candidates agree, which is what Tab completes
+        let (start, candidates) = self.candidates(line)?;
+        let suffix = longest_common_prefix(&candidates)?.strip_prefix(&line[start..])?;
+        if suffix.is_empty() {
+            return None;
+        }
+
+        Some(CommandHint::new(suffix, suffix, Box::new(|_| None)))
+    }
+}
+
+/// Entries of the directory in `typed` whose names start with the rest of it.
+/// Returns where the name starts in `typed` too.
+pub fn complete_paths(typed: &str) -> (usize, Vec<String>) {
+    let (dir, prefix) = match typed.rfind('/') {
+        Some(i) => (&typed[..=i], &typed[i + 1..]),
+        None => ("", typed),
+    };
+
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { dir }) else {
+        return (dir.len(), vec![]);
+    };
+
+    let mut candidates = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            // Hidden files are only completed when asked for explicitly
+            if !name.starts_with(prefix) || (prefix.is_empty() && name.starts_with('.')) {
+                return None;
+            }
+
+            match entry.file_type().ok()?.is_dir() {
+                true => Some(format!("{name}/")),
+                false => Some(name),
             }
         })
+        .collect::<Vec<_>>();
+    candidates.sort();
+
+    (dir.len(), candidates)
+}
+
+/// The `values` that start with `typed`, ignoring case only when none match
+/// exactly
+pub fn complete_values(values: &[String], typed: &str) -> Vec<String> {
+    let matching = values
+        .iter()
+        .filter(|value| value.starts_with(typed))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !matching.is_empty() {
+        return matching;
     }
+
+    let typed = typed.to_lowercase();
+    values
+        .iter()
+        .filter(|value| value.to_lowercase().starts_with(&typed))
+        .cloned()
+        .collect()
 }
 
 impl Highlighter for Hinter {
@@ -62,6 +160,9 @@ impl Highlighter for Hinter {
 }
 
 type CommandFn = Box<dyn Fn(&str) -> Option<String>>;
+/// Completes what was typed after a command: where the completed word starts
+/// in it, and the candidates
+type ArgsFn = Box<dyn Fn(&str) -> (usize, Vec<String>)>;
 
 #[derive(Derivative)]
 #[derivative(Hash, Debug, PartialEq, Eq)]
@@ -70,6 +171,8 @@ pub struct CommandHint {
     complete_up_to: usize,
     #[derivative(Hash = "ignore", Debug = "ignore", PartialEq = "ignore")]
     command: CommandFn,
+    #[derivative(Hash = "ignore", Debug = "ignore", PartialEq = "ignore")]
+    args: Option<ArgsFn>,
 }
 
 impl Hint for CommandHint {
@@ -93,19 +196,17 @@ impl CommandHint {
             display: text.to_owned(),
             complete_up_to: complete_up_to.len(),
             command,
+            args: None,
         }
     }
 
-    pub fn display(&self) -> &str {
-        &self.display
+    /// Complete the command's arguments with `args`
+    pub fn with_args(mut self, args: impl Fn(&str) -> (usize, Vec<String>) + 'static) -> Self {
+        self.args = Some(Box::new(args));
+        self
     }
 
-    fn suffix(&self, strip_chars: usize) -> Self {
-        let hint = self.display[strip_chars..].to_owned();
-        Self {
-            display: hint.clone(),
-            complete_up_to: self.complete_up_to.saturating_sub(strip_chars),
-            command: Box::new(|_| None),
-        }
+    pub fn display(&self) -> &str {
+        &self.display
     }
 }
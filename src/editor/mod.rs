@@ -1,20 +1,50 @@
 mod event_handlers;
 mod hinter;
 
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use console::{measure_text_width, style, Term};
 use hinter::Hinter;
 use radix_trie::Trie;
-use rustyline::{history::DefaultHistory, Cmd, EventHandler, KeyCode, KeyEvent, Modifiers};
+use rustyline::{
+    history::{FileHistory, History},
+    Cmd, CompletionType, Config, Event, EventHandler, KeyCode, KeyEvent, Modifiers,
+};
+
+use crate::utils::paths::data_dir;
+use event_handlers::{ComposeEventHandler, InterruptEventHandler};
+pub use hinter::{complete_paths, complete_values, CommandHint};
 
-use event_handlers::TabEventHandler;
-pub use hinter::CommandHint;
+/// Lines kept in each history file
+const HISTORY_SIZE: usize = 1000;
 
 pub struct Editor {
-    rl: rustyline::Editor<Hinter, DefaultHistory>,
+    rl: rustyline::Editor<Hinter, FileHistory>,
+    /// Buffer to open in the external editor, set by Ctrl-X Ctrl-E
+    compose: Arc<Mutex<Option<String>>>,
+    /// File the input history is kept in, `None` keeps it in memory only
+    history: Option<PathBuf>,
 }
 
 impl Editor {
-    pub fn new(hints: Vec<CommandHint>) -> rustyline::Result<Self> {
-        let mut rl = rustyline::Editor::<Hinter, DefaultHistory>::new()?;
+    /// `history` names the history file, e.g. `chat`. Each prompt has its own.
+    pub fn new(hints: Vec<CommandHint>, history: Option<&str>) -> rustyline::Result<Self> {
+        let config = Config::builder()
+            .max_history_size(HISTORY_SIZE)?
+            .history_ignore_dups(true)?
+            .completion_type(CompletionType::List)
+            .build();
+        let mut rl = rustyline::Editor::<Hinter, FileHistory>::with_config(config)?;
+
+        let history = history.and_then(history_path);
+        if let Some(path) = &history {
+            load_history(&mut rl, path);
+        }
 
         let hints: Trie<String, CommandHint> = hints
             .into_iter()
@@ -24,23 +54,115 @@ impl Editor {
         let hinter = Hinter::new(hints);
         rl.set_helper(Some(hinter));
         rl.bind_sequence(
-            KeyEvent::from('\t'),
-            EventHandler::Conditional(Box::new(TabEventHandler)),
+            KeyEvent::ctrl('C'),
+            EventHandler::Conditional(Box::new(InterruptEventHandler)),
         );
         rl.bind_sequence(
             KeyEvent(KeyCode::Enter, Modifiers::SHIFT),
             EventHandler::Simple(Cmd::Newline),
         );
 
-        Ok(Self { rl })
+        let compose = Arc::new(Mutex::new(None));
+        rl.bind_sequence(
+            Event::KeySeq(vec![KeyEvent::ctrl('X'), KeyEvent::ctrl('E')]),
+            EventHandler::Conditional(Box::new(ComposeEventHandler(compose.clone()))),
+        );
+
+        Ok(Self {
+            rl,
+            compose,
+            history,
+        })
     }
 
     pub fn readline(&mut self) -> rustyline::Result<String> {
-        self.rl.readline("")
+        let line = self.rl.readline("")?;
+        Ok(self.compose_if_asked(line))
+    }
+
+    /// Read a line that starts out with `initial`, ready to be edited
+    pub fn readline_with_initial(&mut self, initial: &str) -> rustyline::Result<String> {
+        let line = self.rl.readline_with_initial("", (initial, ""))?;
+        Ok(self.compose_if_asked(line))
+    }
+
+    /// Ask a single question, e.g. in suggest and explain. Empty if cancelled.
+    pub fn ask(prompt: &str, history: &str) -> String {
+        println!("{prompt}");
+        let Ok(mut editor) = Self::new(vec![], Some(history)) else {
+            return String::new();
+        };
+
+        loop {
+            match editor.readline() {
+                Ok(line) if line.trim().is_empty() => {
+                    let _ = Term::stdout().clear_last_lines(1);
+                }
+                Ok(line) => {
+                    let _ = editor.append_history(&line);
+                    return line;
+                }
+                Err(_) => return String::new(),
+            }
+        }
+    }
+
+    /// Open the external editor for Ctrl-X Ctrl-E or `/editor [text]`, and
+    /// put the saved text in place of the line
+    fn compose_if_asked(&mut self, line: String) -> String {
+        let buffer = self
+            .compose
+            .lock()
+            .ok()
+            .and_then(|mut buffer| buffer.take());
+        let command = line
+            .trim()
+            .strip_prefix("/editor")
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+
+        let initial = match (buffer, command) {
+            (Some(buffer), _) => buffer,
+            (None, Some(rest)) => rest.trim().to_string(),
+            (None, None) => return line,
+        };
+
+        // The line is replaced by the text that is sent
+        let term = Term::stdout();
+        let width = (term.size().1 as usize).max(1);
+        let rows = line
+            .split('\n')
+            .map(|line| measure_text_width(line).div_ceil(width).max(1))
+            .sum();
+        let _ = term.clear_last_lines(rows);
+
+        match compose(&initial) {
+            Ok(Some(text)) => {
+                println!("{text}");
+                text
+            }
+            Ok(None) => {
+                println!();
+                String::new()
+            }
+            Err(e) => {
+                println!("{} {e}\n", style("✗").red());
+                String::new()
+            }
+        }
     }
 
+    /// Add a line to the history and its file. Lines that start with a space
+    /// are left out, like with `HISTCONTROL=ignorespace` in bash.
     pub fn append_history(&mut self, line: &str) -> rustyline::Result<bool> {
-        self.rl.add_history_entry(line)
+        if line.starts_with(char::is_whitespace) || line.trim().is_empty() {
+            return Ok(false);
+        }
+
+        let added = self.rl.add_history_entry(line.trim_end())?;
+        if let (true, Some(path)) = (added, &self.history) {
+            self.rl.append_history(path)?;
+        }
+        Ok(added)
     }
 
     pub fn execute_command(&mut self, line: &str) -> Option<String> {
@@ -50,3 +172,48 @@ impl Editor {
         }
     }
 }
+
+/// Edit `initial` in `$VISUAL` or `$EDITOR`. `None` when the file was not
+/// saved or left empty.
+pub fn compose(initial: &str) -> Result<Option<String>, String> {
+    let text = dialoguer::Editor::new()
+        .extension(".md")
+        .edit(initial)
+        .map_err(|e| format!("Failed to open the editor: {e}"))?;
+
+    Ok(text
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty()))
+}
+
+fn history_path(name: &str) -> Option<PathBuf> {
+    let dir = data_dir()?.join("history");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(format!("{name}.txt")))
+}
+
+/// Load the history file, keeping only the most recent copy of repeated lines
+fn load_history(rl: &mut rustyline::Editor<Hinter, FileHistory>, path: &Path) {
+    if rl.load_history(path).is_err() {
+        return;
+    }
+
+    let mut seen = HashSet::new();
+    let mut lines = rl
+        .history()
+        .iter()
+        .rev()
+        .filter(|line| seen.insert(line.as_str()))
+        .cloned()
+        .collect::<Vec<_>>();
+    if lines.len() == rl.history().len() {
+        return;
+    }
+
+    lines.reverse();
+    let _ = rl.clear_history();
+    for line in lines {
+        let _ = rl.add_history_entry(line);
+    }
+    let _ = rl.save_history(path);
+}
@@ -1,11 +1,16 @@
 mod ai;
 mod args;
+mod attachments;
 mod client;
 mod config;
 mod editor;
+mod manage;
 mod mcp;
 mod program;
 mod provider;
+mod session;
+mod templates;
+mod tools;
 mod utils;
 
 use program::Program;
@@ -13,6 +18,11 @@ use program::Program;
 #[tokio::main]
 async fn main() {
     let Ok(_) = ctrlc::set_handler(move || {
+        // During a generation Ctrl-C only cancels it
+        if utils::interrupt::interrupt() {
+            return;
+        }
+
         let term = console::Term::stdout();
         let _ = term.flush();
         let _ = term.show_cursor();
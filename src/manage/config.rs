@@ -0,0 +1,201 @@
+use std::path::PathBuf;
+
+use crate::{args::ConfigAction, config::Config, tools::shell, utils::enums::ProviderName};
+
+use super::{model::set_model, parse_bool, ManageError, ManageResult};
+
+const KEYS: &[&str] = &[
+    "provider",
+    "model",
+    "search_model",
+    "streaming",
+    "compact_threshold",
+    "stdin_limit_kb",
+    "shell_tool",
+    "shell_timeout_secs",
+    "shell_output_limit_kb",
+    "shell_allow",
+    "shell_deny",
+    "fs_tools",
+    "fs_roots",
+];
+
+pub async fn config(cfg: &mut Config, action: &ConfigAction) -> ManageResult {
+    match action {
+        ConfigAction::Get(key) => println!("{}", get(cfg, key)?),
+        ConfigAction::Set(key, value) => set(cfg, key, value).await?,
+        ConfigAction::List => {
+            for key in KEYS {
+                println!("{key}={}", get(cfg, key)?);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn get(cfg: &Config, key: &str) -> Result<String, ManageError> {
+    let value = match key {
+        "provider" => cfg.active_provider().map(|p| p.name().to_string()),
+        "model" => cfg.active_provider().map(|p| p.completion_model()),
+        "search_model" => cfg.active_search_provider().and_then(|p| p.search_model()),
+        "streaming" => Some(cfg.streaming().to_string()),
+        "compact_threshold" => Some(cfg.compact_threshold().to_string()),
+        "stdin_limit_kb" => Some(cfg.stdin_limit_kb().to_string()),
+        "shell_tool" => Some(cfg.shell_tool().to_string()),
+        "shell_timeout_secs" => Some(cfg.shell_timeout_secs().to_string()),
+        "shell_output_limit_kb" => Some(cfg.shell_output_limit_kb().to_string()),
+        "shell_allow" => Some(cfg.shell_allow().join(",")),
+        "shell_deny" => Some(cfg.shell_deny().join(",")),
+        "fs_tools" => Some(cfg.fs_tools().to_string()),
+        "fs_roots" => Some(
+            cfg.fs_roots()
+                .iter()
+                .map(|root| root.display().to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        _ => return Err(unknown_key(key)),
+    };
+
+    Ok(value.unwrap_or_default())
+}
+
+async fn set(cfg: &mut Config, key: &str, value: &str) -> ManageResult {
+    match key {
+        "provider" => {
+            let provider_name = value.parse::<ProviderName>().map_err(ManageError::usage)?;
+            let Some(provider) = cfg.find_provider(&provider_name) else {
+                return Err(ManageError::failure(format!(
+                    "{provider_name} is not configured. Run `termai provider add {}` first",
+                    value.to_lowercase()
+                )));
+            };
+
+            let model = provider.completion_model();
+            cfg.set_completion_model(provider_name, model);
+        }
+        "model" => set_model(cfg, value, false).await?,
+        "search_model" => set_model(cfg, value, true).await?,
+        "streaming" => {
+            let enabled = parse_bool(value).ok_or_else(|| {
+                ManageError::usage(format!(
+                    "Invalid value '{value}' for streaming (expected true or false)"
+                ))
+            })?;
+            cfg.set_streaming(enabled);
+        }
+        "compact_threshold" => {
+            let percent = value
+                .parse::<u8>()
+                .ok()
+                .filter(|percent| *percent <= 100)
+                .ok_or_else(|| {
+                    ManageError::usage(format!(
+                        "Invalid value '{value}' for compact_threshold (expected a percentage from 0 to 100, 0 disables it)"
+                    ))
+                })?;
+            cfg.set_compact_threshold(percent);
+        }
+        "stdin_limit_kb" => {
+            let limit = value
+                .parse::<u32>()
+                .ok()
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| {
+                    ManageError::usage(format!(
+                        "Invalid value '{value}' for stdin_limit_kb (expected a size in KB greater than 0)"
+                    ))
+                })?;
+            cfg.set_stdin_limit_kb(limit);
+        }
+        "shell_tool" => {
+            let enabled = parse_bool(value).ok_or_else(|| {
+                ManageError::usage(format!(
+                    "Invalid value '{value}' for shell_tool (expected true or false)"
+                ))
+            })?;
+            cfg.set_shell_tool(enabled);
+        }
+        "shell_timeout_secs" => {
+            let secs = parse_positive(key, value, "a number of seconds")?;
+            cfg.set_shell_timeout_secs(secs);
+        }
+        "shell_output_limit_kb" => {
+            let limit = parse_positive(key, value, "a size in KB")?;
+            cfg.set_shell_output_limit_kb(limit);
+        }
+        "shell_allow" => cfg.set_shell_allow(parse_patterns(key, value)?),
+        "shell_deny" => cfg.set_shell_deny(parse_patterns(key, value)?),
+        "fs_tools" => {
+            let enabled = parse_bool(value).ok_or_else(|| {
+                ManageError::usage(format!(
+                    "Invalid value '{value}' for fs_tools (expected true or false)"
+                ))
+            })?;
+            cfg.set_fs_tools(enabled);
+        }
+        "fs_roots" => cfg.set_fs_roots(parse_roots(value)?),
+        _ => return Err(unknown_key(key)),
+    }
+
+    Ok(())
+}
+
+fn parse_positive(key: &str, value: &str, expected: &str) -> Result<u32, ManageError> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|value| *value > 0)
+        .ok_or_else(|| {
+            ManageError::usage(format!(
+                "Invalid value '{value}' for {key} (expected {expected} greater than 0)"
+            ))
+        })
+}
+
+/// Comma-separated glob patterns, an empty value clears the list
+fn parse_patterns(key: &str, value: &str) -> Result<Vec<String>, ManageError> {
+    let patterns = value
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    for pattern in &patterns {
+        shell::pattern(pattern).map_err(|e| {
+            ManageError::usage(format!("Invalid pattern '{pattern}' for {key}: {e}"))
+        })?;
+    }
+
+    Ok(patterns)
+}
+
+/// Comma-separated directories, stored as absolute paths. An empty value
+/// goes back to the working directory.
+fn parse_roots(value: &str) -> Result<Vec<PathBuf>, ManageError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|root| !root.is_empty())
+        .map(|root| {
+            PathBuf::from(root)
+                .canonicalize()
+                .ok()
+                .filter(|path| path.is_dir())
+                .ok_or_else(|| {
+                    ManageError::usage(format!(
+                        "Invalid value '{root}' for fs_roots (not a directory)"
+                    ))
+                })
+        })
+        .collect()
+}
+
+fn unknown_key(key: &str) -> ManageError {
+    ManageError::usage(format!(
+        "Unknown setting '{key}'. Available settings: {}",
+        KEYS.join(", ")
+    ))
+}
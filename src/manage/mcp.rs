@@ -0,0 +1,102 @@
+use crate::{
+    args::McpAction,
+    config::Config,
+    mcp::{McpClient, McpClientConfig},
+    program::VERSION,
+};
+
+use super::{ManageError, ManageResult};
+
+pub async fn mcp(cfg: &mut Config, action: &McpAction) -> ManageResult {
+    match action {
+        McpAction::AddProgram(program, args) => {
+            add(
+                cfg,
+                McpClientConfig::StdIo(
+                    "termai".to_string(),
+                    VERSION.to_string(),
+                    program.to_owned(),
+                    args.to_owned(),
+                    false,
+                ),
+            )
+            .await?
+        }
+        McpAction::AddSse(url) => {
+            add(
+                cfg,
+                McpClientConfig::Sse(
+                    "termai".to_string(),
+                    VERSION.to_string(),
+                    url.to_owned(),
+                    false,
+                ),
+            )
+            .await?
+        }
+        McpAction::Remove(name) => {
+            if !cfg.remove_mcp_client(name) {
+                return Err(not_found(name));
+            }
+        }
+        McpAction::Enable(name) => set_enabled(cfg, name, true)?,
+        McpAction::Disable(name) => set_enabled(cfg, name, false)?,
+        McpAction::List => {
+            for client in cfg.mcp_clients().iter() {
+                let (kind, target) = match McpClientConfig::from(client.clone()) {
+                    McpClientConfig::StdIo(_, _, program, args, _) => {
+                        ("stdio", [vec![program], args].concat().join(" "))
+                    }
+                    McpClientConfig::Sse(_, _, url, _) => ("sse", url),
+                };
+
+                println!(
+                    "{}\t{}\t{kind}\t{target}\t{}",
+                    client.name(),
+                    client.version(),
+                    client.is_enabled()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Test the connection and save the server. New servers start out disabled,
+/// just like when they are added through the options menu.
+async fn add(cfg: &mut Config, config: McpClientConfig) -> ManageResult {
+    let mut client: McpClient = config.into();
+
+    client
+        .initialize()
+        .await
+        .map_err(|e| ManageError::failure(format!("Connection failed: {e}")))?;
+
+    if cfg.mcp_clients().iter().any(|c| c.name() == client.name()) {
+        return Err(ManageError::failure(format!(
+            "{} is already configured",
+            client.name()
+        )));
+    }
+
+    println!("{}", client.name());
+    cfg.add_mcp_client(client);
+
+    Ok(())
+}
+
+fn set_enabled(cfg: &mut Config, name: &str, enabled: bool) -> ManageResult {
+    let Some(client) = cfg.find_mcp_client_mut(name) else {
+        return Err(not_found(name));
+    };
+
+    client.set_enabled(enabled);
+    cfg.save();
+
+    Ok(())
+}
+
+fn not_found(name: &str) -> ManageError {
+    ManageError::failure(format!("No MCP server named '{name}'"))
+}
@@ -0,0 +1,65 @@
+mod config;
+mod mcp;
+mod model;
+mod provider;
+mod sessions;
+
+use console::style;
+
+use crate::{args::Args, config::Config};
+
+/// Exit code for failures caused by invalid input (same as clap uses).
+const EXIT_USAGE: i32 = 2;
+/// Exit code for everything else that went wrong.
+const EXIT_FAILURE: i32 = 1;
+
+pub struct ManageError {
+    code: i32,
+    message: String,
+}
+
+impl ManageError {
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self {
+            code: EXIT_USAGE,
+            message: message.into(),
+        }
+    }
+
+    pub fn failure(message: impl Into<String>) -> Self {
+        Self {
+            code: EXIT_FAILURE,
+            message: message.into(),
+        }
+    }
+}
+
+pub type ManageResult = Result<(), ManageError>;
+
+/// Run a management command and return the process exit code.
+pub async fn run(cfg: &mut Config, args: &Args) -> i32 {
+    let result = match args {
+        Args::Config(action) => config::config(cfg, action).await,
+        Args::Provider(action) => provider::provider(cfg, action).await,
+        Args::Model(action) => model::model(cfg, action).await,
+        Args::Mcp(action) => mcp::mcp(cfg, action).await,
+        Args::Sessions(action) => sessions::sessions(action).await,
+        _ => unreachable!(),
+    };
+
+    match result {
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("{} {}", style("✗").red().bold(), e.message);
+            e.code
+        }
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
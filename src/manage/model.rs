@@ -0,0 +1,75 @@
+use crate::{args::ModelAction, config::Config, utils::enums::ProviderName};
+
+use super::{ManageError, ManageResult};
+
+pub async fn model(cfg: &mut Config, action: &ModelAction) -> ManageResult {
+    match action {
+        ModelAction::Set(model, search) => set_model(cfg, model, *search).await,
+        ModelAction::List(search) => {
+            let models = match search {
+                true => cfg.available_search_models(),
+                false => cfg.available_completion_models(),
+            };
+
+            let active_model = cfg.active_model(*search).map(|(id, _)| id);
+
+            for (provider_name, id, display_name) in models {
+                let active = active_model.as_ref() == Some(id);
+                println!("{id}\t{provider_name}\t{display_name}\t{active}");
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Set the active completion or search model by ID or display name.
+pub async fn set_model(cfg: &mut Config, model: &str, search: bool) -> ManageResult {
+    if cfg.active_provider().is_none() {
+        return Err(ManageError::failure(
+            "No provider configured. Run `termai provider add <name>` first",
+        ));
+    }
+
+    let mut found = find_model(cfg, model, search);
+    if found.is_none() {
+        // The cached model list may be outdated
+        cfg.refresh_available_models().await;
+        found = find_model(cfg, model, search);
+    }
+
+    let Some((provider_name, model_id)) = found else {
+        let list_command = if search {
+            "termai model list --search"
+        } else {
+            "termai model list"
+        };
+        return Err(ManageError::usage(format!(
+            "Unknown model '{model}'. Run `{list_command}` to see available models"
+        )));
+    };
+
+    match search {
+        true => cfg.set_search_model(provider_name, model_id),
+        false => cfg.set_completion_model(provider_name, model_id),
+    }
+
+    Ok(())
+}
+
+fn find_model(cfg: &Config, model: &str, search: bool) -> Option<(ProviderName, String)> {
+    let models = match search {
+        true => cfg.available_search_models(),
+        false => cfg.available_completion_models(),
+    };
+
+    models
+        .iter()
+        .find(|(_, id, _)| id == model)
+        .or_else(|| {
+            models
+                .iter()
+                .find(|(_, _, display_name)| display_name.eq_ignore_ascii_case(model))
+        })
+        .map(|(provider_name, id, _)| (*provider_name, id.to_owned()))
+}
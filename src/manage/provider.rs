@@ -0,0 +1,60 @@
+use std::io::{stdin, IsTerminal, Read};
+
+use crate::{args::ProviderAction, config::Config, utils::enums::ProviderName};
+
+use super::{ManageError, ManageResult};
+
+pub async fn provider(cfg: &mut Config, action: &ProviderAction) -> ManageResult {
+    match action {
+        ProviderAction::Add(provider_name, key_stdin) => {
+            let api_key = read_api_key(*provider_name, *key_stdin)?;
+            cfg.add_provider_api_key(*provider_name, api_key).await;
+        }
+        ProviderAction::Remove(provider_name) => {
+            if !cfg.is_configured(*provider_name) {
+                return Err(ManageError::failure(format!(
+                    "{provider_name} is not configured"
+                )));
+            }
+            cfg.remove_provider(*provider_name);
+        }
+        ProviderAction::List => {
+            let active_provider = cfg.active_provider().map(|p| p.name());
+            for provider_name in ProviderName::iter() {
+                if cfg.is_configured(provider_name) {
+                    let active = active_provider == Some(provider_name);
+                    println!("{provider_name}\t{active}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_api_key(provider_name: ProviderName, key_stdin: bool) -> Result<String, ManageError> {
+    let api_key = if key_stdin {
+        let mut api_key = String::new();
+        stdin()
+            .read_to_string(&mut api_key)
+            .map_err(|e| ManageError::failure(format!("Failed to read API key: {e}")))?;
+        api_key
+    } else if stdin().is_terminal() {
+        dialoguer::Password::new()
+            .with_prompt(format!("Enter your {:?} API key", provider_name))
+            .allow_empty_password(false)
+            .interact()
+            .map_err(|e| ManageError::failure(format!("Failed to read API key: {e}")))?
+    } else {
+        return Err(ManageError::usage(
+            "No terminal available to prompt for the API key. Use --key-stdin",
+        ));
+    };
+
+    let api_key = api_key.trim().to_string();
+    if api_key.is_empty() {
+        return Err(ManageError::usage("The API key must not be empty"));
+    }
+
+    Ok(api_key)
+}
@@ -0,0 +1,89 @@
+use std::{
+    fs,
+    io::{stdin, stdout, IsTerminal, Read},
+    path::Path,
+};
+
+use chrono::Local;
+use termimad::MadSkin;
+
+use crate::{
+    args::SessionsAction,
+    session::{
+        export::{self, ExportFormat},
+        print_entries, Session,
+    },
+};
+
+use super::{ManageError, ManageResult};
+
+pub async fn sessions(action: &SessionsAction) -> ManageResult {
+    match action {
+        SessionsAction::List => print_sessions(&Session::list()),
+        SessionsAction::Search(query) => print_sessions(&Session::search(query)),
+        SessionsAction::Show(id) => {
+            let session = Session::load(id).map_err(ManageError::failure)?;
+
+            if stdout().is_terminal() {
+                print_entries(&session.entries, &MadSkin::default());
+            } else {
+                for entry in session.entries {
+                    println!("[{:?}]\n{}\n", entry.role, entry.content);
+                }
+            }
+        }
+        SessionsAction::Delete(id) => {
+            let session = Session::load(id).map_err(ManageError::failure)?;
+            session.delete().map_err(ManageError::failure)?;
+        }
+        SessionsAction::Export(id, format, output) => {
+            let session = Session::load(id).map_err(ManageError::failure)?;
+
+            let format = match (format, output) {
+                (Some(format), _) => format.parse().map_err(ManageError::usage)?,
+                (None, Some(output)) => {
+                    ExportFormat::from_path(Path::new(output)).unwrap_or(ExportFormat::Markdown)
+                }
+                (None, None) => ExportFormat::Markdown,
+            };
+
+            let exported = export::export(&session, format);
+            match output {
+                Some(output) => fs::write(output, exported)
+                    .map_err(|e| ManageError::failure(format!("Failed to write {output}: {e}")))?,
+                None => print!("{exported}"),
+            }
+        }
+        SessionsAction::Import(file) => {
+            let mut json = String::new();
+            let read = match file.as_str() {
+                "-" => stdin().read_to_string(&mut json).map(|_| ()),
+                file => fs::read_to_string(file).map(|content| json = content),
+            };
+            read.map_err(|e| ManageError::failure(format!("Failed to read {file}: {e}")))?;
+
+            let session = export::import(&json)
+                .and_then(|session| session.import())
+                .map_err(ManageError::failure)?;
+            println!("{}", session.id);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_sessions(sessions: &[Session]) {
+    for session in sessions {
+        println!(
+            "{}\t{}\t{}\t{}\t{}",
+            session.id,
+            session
+                .updated_at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M"),
+            session.entries.len(),
+            session.model,
+            session.title
+        );
+    }
+}
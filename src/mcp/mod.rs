@@ -85,23 +85,30 @@ impl McpClient {
         Ok(())
     }
 
+    /// Tools named like a built-in tool in `reserved` are left out
     pub async fn add_tools<M: CompletionModel>(
         &self,
         agent_builder: AgentBuilder<M>,
+        reserved: &[&str],
     ) -> AgentBuilder<M> {
-        let tools = self.tools().await;
+        let tools = self
+            .tools()
+            .await
+            .iter()
+            .filter(|tool| !reserved.contains(&tool.name.as_str()));
 
         match self {
-            McpClient::StdIo(client, _) => tools.iter().fold(agent_builder, |builder, tool| {
+            McpClient::StdIo(client, _) => tools.fold(agent_builder, |builder, tool| {
                 builder.mcp_tool(tool.clone(), client.clone())
             }),
-            McpClient::Sse(client, _) => tools.iter().fold(agent_builder, |builder, tool| {
+            McpClient::Sse(client, _) => tools.fold(agent_builder, |builder, tool| {
                 builder.mcp_tool(tool.clone(), client.clone())
             }),
         }
     }
 
-    async fn tools(&self) -> &Vec<Tool> {
+    /// Tools the server offered when it was initialized
+    pub async fn tools(&self) -> &Vec<Tool> {
         match self {
             McpClient::StdIo(_, info) => &info.tools,
             McpClient::Sse(_, info) => &info.tools,
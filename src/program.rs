@@ -3,11 +3,17 @@ use dialoguer::{MultiSelect, Select};
 use std::{env, time::Duration};
 
 use crate::{
-    ai::AI,
+    ai::{ChatOptions, JsonRequest, AI},
     args::{Args, ChatArgs},
+    attachments::stdin::Piped,
     config::Config,
+    manage,
     mcp::{McpClient, McpClientConfig},
-    utils::{changelog, console::get_select_theme, enums::ProviderName},
+    utils::{
+        changelog,
+        console::get_select_theme,
+        enums::{OutputFormat, ProviderName},
+    },
 };
 
 pub static VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -33,6 +39,29 @@ impl Program {
     pub async fn run() {
         let mut program = Program::default();
 
+        if program.args.is_management() {
+            let code = manage::run(&mut program.cfg, &program.args).await;
+            std::process::exit(code);
+        }
+
+        if let Some((command, args)) = program.args.chat_args() {
+            if args.output() == OutputFormat::Json {
+                let args = args.to_owned();
+                let code = program.json(command, args).await;
+                std::process::exit(code);
+            }
+        }
+
+        if let Args::Ask((_, args)) = &program.args {
+            let code = program.ask(args.to_owned()).await;
+            std::process::exit(code);
+        }
+
+        if let Args::Compare((_, args)) = &program.args {
+            let code = program.compare(args.to_owned()).await;
+            std::process::exit(code);
+        }
+
         let welome_msg = style("Welcome to TermAI - Your AI in the Terminal").bold();
         let version_msg = style(format!("version {} ({})", VERSION, RELEASE_DATE)).dim();
         println!("\n{welome_msg}\n{version_msg}");
@@ -301,7 +330,14 @@ impl Program {
                 self.options_menu().await
             }
             Args::Changelog => changelog::print_latest(),
-            Args::None => unreachable!(),
+            Args::Ask(_)
+            | Args::Compare(_)
+            | Args::Config(_)
+            | Args::Provider(_)
+            | Args::Model(_)
+            | Args::Mcp(_)
+            | Args::Sessions(_)
+            | Args::None => unreachable!(),
         }
     }
 
@@ -332,23 +368,118 @@ impl Program {
             std::process::exit(1);
         };
 
-        let (prompt, model, search) = if let Some(args) = args {
-            (args.prompt(), args.model(), args.search())
+        let (prompt, model, search, resume, incognito, files) = if let Some(args) = args {
+            (
+                args.prompt(),
+                args.model(),
+                args.search(),
+                args.resume(),
+                args.incognito(),
+                args.files(),
+            )
         } else {
-            (None, false, false)
+            (None, false, false, None, false, vec![])
         };
 
+        let stdin = Piped::read(self.cfg.stdin_limit_kb() as usize * 1024);
+
         let mut ai = AI::new(&self.term, &mut self.cfg);
         match choice {
-            "chat" => ai.chat(prompt, model, search).await,
-            "suggest" => ai.suggest(prompt, model).await,
-            "explain" => ai.explain(prompt, model).await,
+            "chat" => {
+                ai.chat(ChatOptions {
+                    initial_message: prompt,
+                    select_model: model,
+                    search,
+                    resume,
+                    incognito,
+                    files,
+                    stdin,
+                })
+                .await
+            }
+            "suggest" => ai.suggest(prompt, model, files, stdin).await,
+            "explain" => ai.explain(prompt, model, files, stdin).await,
             _ => Program::help(),
         }
 
         std::process::exit(0);
     }
 
+    /// Print the answers of several models to the same prompt
+    async fn compare(&mut self, args: ChatArgs) -> i32 {
+        let stdin = Piped::read(self.cfg.stdin_limit_kb() as usize * 1024);
+        let prompt = args.prompt().unwrap_or_default();
+
+        if prompt.trim().is_empty() && stdin.is_none() {
+            eprintln!(
+                "{} Please provide a prompt or pipe something into termai",
+                style("✗").red()
+            );
+            return 2;
+        }
+
+        let models = args.models().unwrap_or_default();
+        let ai = AI::new(&self.term, &mut self.cfg);
+        match ai
+            .compare(
+                &models,
+                &prompt,
+                args.columns(),
+                &args.files(),
+                stdin.as_ref(),
+            )
+            .await
+        {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("{} {e}", style("✗").red());
+                1
+            }
+        }
+    }
+
+    /// Answer a single prompt with nothing but the answer on stdout
+    async fn ask(&mut self, args: ChatArgs) -> i32 {
+        let stdin = Piped::read(self.cfg.stdin_limit_kb() as usize * 1024);
+        let prompt = args.prompt().unwrap_or_default();
+
+        if prompt.trim().is_empty() && stdin.is_none() {
+            eprintln!(
+                "{} Please provide a prompt or pipe something into termai",
+                style("✗").red()
+            );
+            return 2;
+        }
+
+        let ai = AI::new(&self.term, &mut self.cfg);
+        match ai.ask(prompt, args.search(), args.files(), stdin).await {
+            Ok(answer) => {
+                println!("{}", answer.text.trim_end());
+                0
+            }
+            Err(e) => {
+                eprintln!("{} {e}", style("✗").red());
+                1
+            }
+        }
+    }
+
+    /// Answer a single prompt with a JSON object on stdout
+    async fn json(&mut self, command: &'static str, args: ChatArgs) -> i32 {
+        let stdin = Piped::read(self.cfg.stdin_limit_kb() as usize * 1024);
+
+        let mut ai = AI::new(&self.term, &mut self.cfg);
+        ai.json(JsonRequest {
+            command,
+            prompt: args.prompt().unwrap_or_default(),
+            search: args.search(),
+            incognito: args.incognito(),
+            files: args.files(),
+            stdin,
+        })
+        .await
+    }
+
     async fn configure_provider(&mut self, provider_name: ProviderName) {
         let Ok(api_key) = dialoguer::Password::new()
             .with_prompt(format!("Enter your {:?} API key", provider_name))
@@ -541,8 +672,15 @@ impl Program {
         println!("\nUsage: termai [OPTION] [ARG]\n");
         println!("Options:");
         println!("  chat    [ARG]  Chat with the AI (optional string argument)");
+        println!("  ask     [ARG]  Ask a single question and print only the answer");
+        println!("  compare [ARG]  Send one prompt to several models and compare the answers");
         println!("  suggest [ARG]  Get suggestions from the AI (optional string argument)");
         println!("  explain [ARG]  Get explanations from the AI (optional string argument)");
         println!("  options        Configure TermAI");
+        println!("  config         Get, set and list settings");
+        println!("  provider       Add, remove and list providers");
+        println!("  model          Set and list the active models");
+        println!("  mcp            Add, remove, enable, disable and list MCP servers");
+        println!("  sessions       List, show, search and delete saved chat sessions");
     }
 }
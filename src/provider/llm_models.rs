@@ -1,3 +1,5 @@
+use crate::{attachments::media::MediaKind, utils::enums::ProviderName};
+
 /// Competion models for Anthropic
 ///
 /// Tuple format: `(model_id, display_name)`
@@ -38,6 +40,48 @@ pub const OPENAI_SEARCH_MODELS: &[(&str, &str)] = &[
     ("gpt-4o-mini-search-preview", "GPT 4o-mini Search"),
 ];
 
+/// Context window of a model in tokens. Unknown models get a conservative default.
+pub fn context_window(model_id: &str) -> usize {
+    match model_id {
+        "gpt-4.1" | "gpt-4.1-mini" | "gpt-4.1-nano" => 1_047_576,
+        "o3-mini" | "o4-mini" => 200_000,
+        "gpt-4o" | "gpt-4o-mini" | "gpt-4o-search-preview" | "gpt-4o-mini-search-preview" => {
+            128_000
+        }
+        id if id.starts_with("claude-") => 200_000,
+        _ => 128_000,
+    }
+}
+
+/// Whether a model accepts images or PDFs as input. PDFs are only sent to
+/// Anthropic, since OpenAI chat completions take them as plain text.
+pub fn accepts(model_id: &str, provider_name: &ProviderName, kind: MediaKind) -> bool {
+    match (provider_name, kind) {
+        (ProviderName::Anthropic, MediaKind::Image) => model_id.starts_with("claude-"),
+        (ProviderName::Anthropic, MediaKind::Pdf) => [
+            "claude-sonnet-4",
+            "claude-opus-4",
+            "claude-3-7-sonnet",
+            "claude-3-5-sonnet",
+        ]
+        .iter()
+        .any(|prefix| model_id.starts_with(prefix)),
+        (ProviderName::OpenAI, MediaKind::Image) => {
+            matches!(model_id, "gpt-4o" | "gpt-4o-mini" | "o4-mini")
+                || model_id.starts_with("gpt-4.1")
+        }
+        (ProviderName::OpenAI, MediaKind::Pdf) => false,
+    }
+}
+
+/// Cheap model used for housekeeping tasks such as summarizing old messages
+pub fn summary_model(provider_name: ProviderName) -> &'static str {
+    match provider_name {
+        ProviderName::OpenAI => "gpt-4.1-nano",
+        ProviderName::Anthropic => "claude-3-5-haiku-20241022",
+    }
+}
+
 // /// Completion models for Perplexity
 // ///
 // /// Tuple format: `(model_id, display_name)`
@@ -1,10 +1,11 @@
 use indicatif::ProgressBar;
-use rig::{completion::PromptError, message::Message};
+use rig::message::Message;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    client::{Client, StreamingContentResult},
+    client::{ChatError, ChatResponse, Client, StreamingContentResult},
     mcp::McpClient,
+    tools::BuiltinTools,
     utils::{encryption::Enc, enums::ProviderName},
 };
 
@@ -127,41 +128,43 @@ impl Provider {
 
     pub async fn chat(
         &self,
-        prompt: &str,
+        prompt: Message,
         messages: Vec<Message>,
         mcp_clients: &mut Vec<McpClient>,
+        tools: &BuiltinTools,
         spinner: &ProgressBar,
         search: bool,
-    ) -> Result<String, PromptError> {
-        Client::chat(prompt, messages, self, mcp_clients, spinner, search).await
+    ) -> Result<ChatResponse, ChatError> {
+        Client::chat(prompt, messages, self, mcp_clients, tools, spinner, search).await
     }
 
     pub async fn chat_stream(
         &self,
-        prompt: &str,
+        prompt: Message,
         messages: Vec<Message>,
         mcp_clients: &mut Vec<McpClient>,
+        tools: &BuiltinTools,
         search: bool,
     ) -> StreamingContentResult {
-        Client::chat_stream(prompt, messages, self, mcp_clients, search).await
+        Client::chat_stream(prompt, messages, self, mcp_clients, tools, search).await
     }
 
-    pub async fn suggest(&self, prompt: &str) -> Result<String, PromptError> {
+    pub async fn suggest(&self, prompt: &str) -> Result<ChatResponse, ChatError> {
         Client::suggest(prompt, self).await
     }
 
-    pub async fn revise(
-        &self,
-        prompt: &str,
-        command_to_revise: &str,
-    ) -> Result<String, PromptError> {
+    pub async fn revise(&self, prompt: &str, command_to_revise: &str) -> Result<String, ChatError> {
         Client::revise(prompt, command_to_revise, self).await
     }
 
-    pub async fn explain(&self, prompt: &str) -> Result<String, PromptError> {
+    pub async fn explain(&self, prompt: &str) -> Result<ChatResponse, ChatError> {
         Client::explain(prompt, self).await
     }
 
+    pub async fn summarize(&self, transcript: &str) -> Result<String, ChatError> {
+        Client::summarize(transcript, self).await
+    }
+
     pub async fn fetch_available_models(&self) -> (Vec<(String, String)>, Vec<(String, String)>) {
         let models = match self {
             Provider::OpenAI(_) => {
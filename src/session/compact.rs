@@ -0,0 +1,113 @@
+use chrono::Utc;
+
+use super::{Entry, Role, Session};
+
+/// Number of recent entries that automatic compaction leaves untouched
+pub const KEEP_RECENT_ENTRIES: usize = 4;
+
+/// Rough token estimate (about four characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+/// Summaries are sent to the model as a user message
+pub fn summary_message(summary: &str) -> String {
+    format!("[Summary of the earlier conversation]\n{summary}")
+}
+
+impl Session {
+    /// Estimated size of the history that is sent to the model
+    pub fn estimated_tokens(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| !e.compacted)
+            .map(|e| {
+                let attachments = e.attachments.iter().map(|a| a.estimated_tokens());
+                estimate_tokens(&e.content) + attachments.sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Indices of the entries that can be compacted: everything that is not
+    /// pinned, except the most recent `keep` entries. The kept part always
+    /// starts with a user message.
+    pub fn compactable(&self, keep: usize) -> Vec<usize> {
+        let mut start = self.entries.len().saturating_sub(keep);
+        while start > 0 && self.entries[start].role != Role::User {
+            start -= 1;
+        }
+
+        (0..start)
+            .filter(|&i| !self.entries[i].compacted && !self.entries[i].pinned)
+            .collect()
+    }
+
+    /// Plain text transcript of the given entries, used as input for summaries
+    pub fn transcript(&self, indices: &[usize]) -> String {
+        indices
+            .iter()
+            .map(|&i| {
+                let entry = &self.entries[i];
+                let speaker = match entry.role {
+                    Role::User => "User",
+                    Role::Assistant => "Assistant",
+                    Role::Summary => "Earlier summary",
+                };
+                let mut text = format!("{speaker}: {}", entry.content);
+                for attachment in &entry.attachments {
+                    text += &format!("\n[Attached: {}]", attachment.name);
+                }
+                text
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Stop sending the given entries to the model, the summary takes their
+    /// place in the history
+    pub fn compact(&mut self, indices: &[usize], summary: String) {
+        let Some(&last) = indices.iter().max() else {
+            return;
+        };
+
+        for &i in indices {
+            self.entries[i].compacted = true;
+        }
+
+        self.updated_at = Utc::now();
+        self.entries.insert(
+            last + 1,
+            Entry {
+                role: Role::Summary,
+                content: summary,
+                model: None,
+                tool_calls: vec![],
+                attachments: vec![],
+                timestamp: self.updated_at,
+                compacted: false,
+                pinned: false,
+                alternates: vec![],
+            },
+        );
+    }
+
+    /// Pin the last exchange so it is never compacted. Returns the number of
+    /// entries that were pinned.
+    pub fn pin_last_exchange(&mut self) -> usize {
+        let Some(start) = self.entries.iter().rposition(|e| e.role == Role::User) else {
+            return 0;
+        };
+
+        for entry in self.entries[start..].iter_mut() {
+            entry.pinned = true;
+        }
+
+        self.entries.len() - start
+    }
+
+    pub fn unpin_all(&mut self) {
+        for entry in self.entries.iter_mut() {
+            entry.pinned = false;
+        }
+    }
+}
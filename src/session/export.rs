@@ -0,0 +1,266 @@
+use std::{fmt::Write, path::Path, str::FromStr};
+
+use chrono::{DateTime, Local, Utc};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+use crate::attachments::media::MediaKind;
+
+use super::{Entry, Role, Session};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+impl ExportFormat {
+    /// Guess the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "json" => Ok(ExportFormat::Json),
+            "html" | "htm" => Ok(ExportFormat::Html),
+            _ => Err(format!(
+                "Unknown export format '{s}' (expected markdown, json or html)"
+            )),
+        }
+    }
+}
+
+pub fn export(session: &Session, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Markdown => to_markdown(session),
+        ExportFormat::Json => {
+            serde_json::to_string_pretty(session).expect("Session is always serializable")
+        }
+        ExportFormat::Html => to_html(session),
+    }
+}
+
+/// Parse a session exported as JSON
+pub fn import(json: &str) -> Result<Session, String> {
+    serde_json::from_str(json).map_err(|e| format!("Invalid session file: {e}"))
+}
+
+fn to_markdown(session: &Session) -> String {
+    let mut md = String::new();
+
+    let _ = writeln!(md, "# {}\n", title(session));
+    let _ = writeln!(md, "- **Session:** {}", session.id);
+    let _ = writeln!(md, "- **Created:** {}", timestamp(&session.created_at));
+    let _ = writeln!(md, "- **Updated:** {}", timestamp(&session.updated_at));
+    let _ = writeln!(md, "- **Models:** {}", session.models().join(", "));
+
+    for entry in &session.entries {
+        let _ = writeln!(md, "\n---\n\n### {}\n", heading(entry));
+
+        for tool_call in &entry.tool_calls {
+            let arguments = serde_json::to_string_pretty(&tool_call.arguments).unwrap_or_default();
+            let _ = writeln!(
+                md,
+                "**Tool call:** `{}` ({})\n\n{}\n**Tool result:**\n\n{}",
+                tool_call.name,
+                timestamp(&tool_call.timestamp),
+                fenced(&arguments, "json"),
+                fenced(&tool_call.result, "")
+            );
+        }
+
+        let _ = writeln!(md, "{}", entry.content.trim_end());
+
+        for attachment in &entry.attachments {
+            let _ = writeln!(md, "\n📎 *Attached: {}*", attachment.name);
+        }
+    }
+
+    md
+}
+
+fn to_html(session: &Session) -> String {
+    let mut body = String::new();
+
+    let _ = writeln!(body, "<h1>{}</h1>", escape(&title(session)));
+    let _ = writeln!(body, "<dl class=\"meta\">");
+    for (term, value) in [
+        ("Session", session.id.to_owned()),
+        ("Created", timestamp(&session.created_at)),
+        ("Updated", timestamp(&session.updated_at)),
+        ("Models", session.models().join(", ")),
+    ] {
+        let _ = writeln!(body, "<dt>{term}</dt><dd>{}</dd>", escape(&value));
+    }
+    let _ = writeln!(body, "</dl>");
+
+    for entry in &session.entries {
+        let class = match entry.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+            Role::Summary => "summary",
+        };
+        let _ = writeln!(body, "<section class=\"entry {class}\">");
+        let _ = writeln!(body, "<h2>{}</h2>", escape(&heading(entry)));
+
+        for tool_call in &entry.tool_calls {
+            let arguments = serde_json::to_string_pretty(&tool_call.arguments).unwrap_or_default();
+            let _ = writeln!(
+                body,
+                "<details class=\"tool\"><summary>Tool call: <code>{}</code> ({})</summary>\
+                 <h3>Arguments</h3><pre><code>{}</code></pre>\
+                 <h3>Result</h3><pre><code>{}</code></pre></details>",
+                escape(&tool_call.name),
+                escape(&timestamp(&tool_call.timestamp)),
+                escape(&arguments),
+                escape(&tool_call.result)
+            );
+        }
+
+        let _ = writeln!(body, "{}", markdown_to_html(&entry.content));
+
+        for attachment in &entry.attachments {
+            match attachment.kind {
+                MediaKind::Image => {
+                    let _ = writeln!(
+                        body,
+                        "<figure><img src=\"data:{};base64,{}\" alt=\"{name}\"><figcaption>{name}</figcaption></figure>",
+                        escape(&attachment.media_type),
+                        attachment.data,
+                        name = escape(&attachment.name)
+                    );
+                }
+                MediaKind::Pdf => {
+                    let _ = writeln!(
+                        body,
+                        "<p class=\"attachment\">📎 Attached: {}</p>",
+                        escape(&attachment.name)
+                    );
+                }
+            }
+        }
+
+        let _ = writeln!(body, "</section>");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>{HTML_STYLE}</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        title = escape(&title(session)),
+    )
+}
+
+/// Render markdown to HTML. Raw HTML and script links from the conversation
+/// are neutralized so the exported page can be opened safely.
+fn markdown_to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if dest_url.trim().to_lowercase().starts_with("javascript:") => {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url: CowStr::Borrowed("#"),
+                title,
+                id,
+            })
+        }
+        event => event,
+    });
+
+    let mut output = String::new();
+    html::push_html(&mut output, parser);
+    output
+}
+
+fn title(session: &Session) -> String {
+    match session.title.is_empty() {
+        true => "Untitled session".to_string(),
+        false => session.title.to_owned(),
+    }
+}
+
+fn heading(entry: &Entry) -> String {
+    let time = timestamp(&entry.timestamp);
+    match (entry.role, &entry.model) {
+        (Role::User, _) => format!("You — {time}"),
+        (Role::Assistant, Some(model)) => format!("AI ({model}) — {time}"),
+        (Role::Assistant, None) => format!("AI — {time}"),
+        (Role::Summary, _) => format!("Summary of earlier messages — {time}"),
+    }
+}
+
+fn timestamp(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S %:z")
+        .to_string()
+}
+
+/// Wrap `text` in a code fence that is longer than any backtick run inside it
+pub fn fenced(text: &str, language: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in text.chars() {
+        if c == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}{language}\n{}\n{fence}\n", text.trim_end())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = r#"
+:root { color-scheme: light dark; --border: #8884; --muted: #888; }
+body { font-family: system-ui, sans-serif; line-height: 1.5; max-width: 52rem; margin: 2rem auto; padding: 0 1rem; }
+h1 { margin-bottom: 0.5rem; }
+h2 { font-size: 0.95rem; color: var(--muted); margin: 0 0 0.5rem; }
+h3 { font-size: 0.85rem; margin: 0.75rem 0 0.25rem; }
+.meta { display: grid; grid-template-columns: max-content 1fr; gap: 0.1rem 1rem; color: var(--muted); }
+.meta dt { font-weight: bold; }
+.meta dd { margin: 0; }
+.entry { border-top: 1px solid var(--border); padding: 1rem 0; }
+.entry.user h2 { color: #2a9d8f; }
+.entry.assistant h2 { color: #6a994e; }
+.entry.summary { color: var(--muted); }
+pre { background: #8881; border: 1px solid var(--border); border-radius: 4px; padding: 0.75rem; overflow-x: auto; }
+code { font-family: ui-monospace, monospace; font-size: 0.9em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid var(--border); padding: 0.25rem 0.5rem; }
+blockquote { border-left: 3px solid var(--border); margin-left: 0; padding-left: 1rem; color: var(--muted); }
+.tool { border: 1px dashed var(--border); border-radius: 4px; padding: 0.5rem 0.75rem; margin-bottom: 0.75rem; }
+.tool summary { cursor: pointer; }
+figure { margin: 0.75rem 0; }
+figure img { max-width: 100%; border: 1px solid var(--border); border-radius: 4px; }
+figcaption, .attachment { font-size: 0.85rem; color: var(--muted); }
+"#;
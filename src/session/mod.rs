@@ -0,0 +1,329 @@
+pub mod compact;
+pub mod export;
+pub mod turns;
+
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use console::style;
+use rand::RngCore;
+use rig::message::Message;
+use serde::{Deserialize, Serialize};
+use termimad::MadSkin;
+
+use crate::{
+    attachments::media::{self, Attachment},
+    client::ToolCallRecord,
+    utils::{enums::ProviderName, paths::data_dir},
+};
+
+const TITLE_MAX_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+    /// Summary of older entries that were compacted
+    Summary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub role: Role,
+    pub content: String,
+    /// Model that produced the entry (assistant entries only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tools the model called before answering (assistant entries only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Images and PDFs sent with the message (user entries only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    pub timestamp: DateTime<Utc>,
+    /// Compacted entries are kept for the record but no longer sent to the model
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compacted: bool,
+    /// Pinned entries are never compacted
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Other answers to the same message, from `/retry` (assistant entries only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternates: Vec<Alternate>,
+}
+
+/// An answer that was regenerated. Only the selected answer is in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alternate {
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCallRecord>,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    pub title: String,
+    /// Model of the most recent answer
+    pub model: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub entries: Vec<Entry>,
+    /// Incognito sessions are never written to disk
+    #[serde(skip)]
+    incognito: bool,
+}
+
+impl Session {
+    pub fn new(model: &str, incognito: bool) -> Self {
+        let mut id = [0u8; 4];
+        rand::rng().fill_bytes(&mut id);
+
+        let now = Utc::now();
+        Self {
+            id: hex::encode(id),
+            title: String::new(),
+            model: model.to_string(),
+            created_at: now,
+            updated_at: now,
+            entries: vec![],
+            incognito,
+        }
+    }
+
+    pub fn is_incognito(&self) -> bool {
+        self.incognito
+    }
+
+    pub fn set_incognito(&mut self, incognito: bool) {
+        self.incognito = incognito;
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = truncate(title.trim(), TITLE_MAX_CHARS);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn push_user(&mut self, content: &str, attachments: Vec<Attachment>) {
+        if self.title.is_empty() {
+            let first_line = content.lines().find(|l| !l.trim().is_empty());
+            self.set_title(first_line.unwrap_or_default());
+        }
+
+        self.push(Role::User, content, None, vec![]);
+        if let Some(entry) = self.entries.last_mut() {
+            entry.attachments = attachments;
+        }
+    }
+
+    pub fn push_assistant(&mut self, content: &str, model: &str, tool_calls: Vec<ToolCallRecord>) {
+        self.model = model.to_string();
+        self.push(
+            Role::Assistant,
+            content,
+            Some(model.to_string()),
+            tool_calls,
+        );
+    }
+
+    /// Distinct models that answered in this session, in order of first use
+    pub fn models(&self) -> Vec<&str> {
+        let mut models = vec![];
+        for model in self.entries.iter().filter_map(|e| e.model.as_deref()) {
+            if !models.contains(&model) {
+                models.push(model);
+            }
+        }
+        models
+    }
+
+    /// The conversation as rig messages, ready to be sent as chat history
+    pub fn messages(&self, provider: &ProviderName, model_id: &str) -> Vec<Message> {
+        self.entries
+            .iter()
+            .filter(|entry| !entry.compacted)
+            .map(|entry| match entry.role {
+                Role::User => {
+                    media::user_message(&entry.content, &entry.attachments, provider, model_id)
+                }
+                Role::Assistant => Message::assistant(&entry.content),
+                Role::Summary => Message::user(compact::summary_message(&entry.content)),
+            })
+            .collect()
+    }
+
+    /// Write the session to disk. Empty and incognito sessions are skipped.
+    pub fn save(&self) -> Result<(), String> {
+        if self.incognito || self.is_empty() {
+            return Ok(());
+        }
+
+        let path = self.path()?;
+        let dir = sessions_dir()?;
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+
+        // Write to a temporary file first so an interrupted write never
+        // leaves a truncated session behind
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, json).map_err(|e| format!("Failed to save session: {e}"))?;
+        fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to save session: {e}"))
+    }
+
+    /// Load a session by its ID or an unambiguous prefix of it
+    pub fn load(id: &str) -> Result<Self, String> {
+        let matches = Self::list()
+            .into_iter()
+            .filter(|s| s.id.starts_with(id))
+            .collect::<Vec<_>>();
+
+        match matches.len() {
+            0 => Err(format!("No session found with ID '{id}'")),
+            1 => Ok(matches.into_iter().next().unwrap()),
+            _ => Err(format!("Session ID '{id}' is ambiguous")),
+        }
+    }
+
+    /// The most recently updated session
+    pub fn latest() -> Option<Self> {
+        Self::list().into_iter().next()
+    }
+
+    /// All saved sessions, most recently updated first
+    pub fn list() -> Vec<Self> {
+        let Ok(dir) = sessions_dir() else {
+            return vec![];
+        };
+
+        let Ok(files) = fs::read_dir(dir) else {
+            return vec![];
+        };
+
+        let mut sessions = files
+            .filter_map(|file| file.ok())
+            .map(|file| file.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|json| serde_json::from_str::<Session>(&json).ok())
+            .collect::<Vec<_>>();
+
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+        sessions
+    }
+
+    /// Sessions where the title or any message contains `query` (case insensitive)
+    pub fn search(query: &str) -> Vec<Self> {
+        let query = query.to_lowercase();
+
+        Self::list()
+            .into_iter()
+            .filter(|s| {
+                s.title.to_lowercase().contains(&query)
+                    || s.entries
+                        .iter()
+                        .any(|e| e.content.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    pub fn delete(&self) -> Result<(), String> {
+        fs::remove_file(self.path()?).map_err(|e| format!("Failed to delete session: {e}"))
+    }
+
+    /// Save an imported session under a new ID. The ID in the file isn't
+    /// trusted, since it becomes a file name.
+    pub fn import(mut self) -> Result<Self, String> {
+        let taken = Self::list().into_iter().map(|s| s.id).collect::<Vec<_>>();
+        self.id = Self::new(&self.model, false).id;
+        while taken.contains(&self.id) {
+            self.id = Self::new(&self.model, false).id;
+        }
+
+        self.incognito = false;
+        self.save()?;
+        Ok(self)
+    }
+
+    /// File of the session. IDs are 8 lowercase hex characters, anything
+    /// else could point outside the sessions directory.
+    fn path(&self) -> Result<PathBuf, String> {
+        let valid = self.id.len() == 8
+            && self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c));
+        if !valid {
+            return Err(format!("Invalid session ID '{}'", self.id));
+        }
+
+        Ok(sessions_dir()?.join(format!("{}.json", self.id)))
+    }
+
+    fn push(
+        &mut self,
+        role: Role,
+        content: &str,
+        model: Option<String>,
+        tool_calls: Vec<ToolCallRecord>,
+    ) {
+        self.updated_at = Utc::now();
+        self.entries.push(Entry {
+            role,
+            content: content.to_string(),
+            model,
+            tool_calls,
+            attachments: vec![],
+            timestamp: self.updated_at,
+            compacted: false,
+            pinned: false,
+            alternates: vec![],
+        });
+    }
+}
+
+/// Print entries the same way they appear in chat
+pub fn print_entries(entries: &[Entry], skin: &MadSkin) {
+    for entry in entries {
+        match entry.role {
+            Role::User => {
+                println!("{}\n{}", style("You:").bold().cyan(), entry.content);
+                for attachment in &entry.attachments {
+                    crate::attachments::print_attachment(attachment);
+                }
+                println!();
+            }
+            Role::Assistant => {
+                println!("{}", style("AI:").bold().green());
+                skin.print_text(&entry.content);
+                println!();
+            }
+            Role::Summary => {
+                println!("{}", style("Summary of earlier messages:").bold().dim());
+                println!("{}\n", style(&entry.content).dim());
+            }
+        }
+    }
+}
+
+fn sessions_dir() -> Result<PathBuf, String> {
+    data_dir()
+        .map(|dir| dir.join("sessions"))
+        .ok_or_else(|| "Unable to determine the data directory".to_string())
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let truncated = text.chars().take(max_chars - 1).collect::<String>();
+    format!("{}…", truncated.trim_end())
+}
@@ -0,0 +1,124 @@
+use chrono::Utc;
+
+use super::{Alternate, Entry, Role, Session};
+use crate::client::ToolCallRecord;
+
+/// A user message and the answer to it, removed from the session
+#[derive(Clone)]
+pub struct Exchange {
+    pub user: Entry,
+    pub assistant: Option<Entry>,
+}
+
+impl Session {
+    /// The answer at the end of the history, if the history ends with one
+    pub fn last_answer(&self) -> Option<&str> {
+        self.entries
+            .last()
+            .filter(|e| e.role == Role::Assistant && !e.compacted)
+            .map(|e| e.content.as_str())
+    }
+
+    /// Remove the last user message and its answer, if any. Nothing is
+    /// removed when the history ends with a summary.
+    pub fn pop_exchange(&mut self) -> Option<Exchange> {
+        let assistant = match self.entries.last()?.role {
+            Role::Assistant => self.entries.pop(),
+            Role::User => None,
+            Role::Summary => return None,
+        };
+
+        match self.entries.last().map(|e| e.role) {
+            Some(Role::User) => Some(Exchange {
+                user: self.entries.pop()?,
+                assistant,
+            }),
+            _ => {
+                // An answer without a question (compacted away) is left alone
+                self.entries.extend(assistant);
+                None
+            }
+        }
+    }
+
+    /// Put a popped exchange back, e.g. when an edit is cancelled
+    pub fn restore_exchange(&mut self, exchange: Exchange) {
+        self.entries.push(exchange.user);
+        self.entries.extend(exchange.assistant);
+    }
+
+    /// Keep the answers of a retried exchange as alternates of the new answer
+    pub fn set_alternates(&mut self, previous: Entry) {
+        let Some(entry) = self.entries.last_mut() else {
+            return;
+        };
+        if entry.role != Role::Assistant {
+            return;
+        }
+
+        let mut alternates = previous.alternates;
+        alternates.push(Alternate {
+            content: previous.content,
+            model: previous.model,
+            tool_calls: previous.tool_calls,
+            timestamp: previous.timestamp,
+        });
+        entry.alternates = alternates;
+    }
+
+    /// Keep another answer to the last message, e.g. from a comparison
+    pub fn add_alternate(&mut self, content: &str, model: &str, tool_calls: Vec<ToolCallRecord>) {
+        let Some(entry) = self
+            .entries
+            .last_mut()
+            .filter(|e| e.role == Role::Assistant)
+        else {
+            return;
+        };
+
+        entry.alternates.push(Alternate {
+            content: content.to_string(),
+            model: Some(model.to_string()),
+            tool_calls,
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Swap the last answer with the next alternate, oldest first and
+    /// wrapping around. Returns the position of the shown answer and the
+    /// number of answers.
+    pub fn next_alternate(&mut self) -> Option<(usize, usize)> {
+        let entry = self
+            .entries
+            .last_mut()
+            .filter(|e| e.role == Role::Assistant && !e.alternates.is_empty())?;
+
+        let mut answers = std::mem::take(&mut entry.alternates);
+        answers.push(Alternate {
+            content: std::mem::take(&mut entry.content),
+            model: entry.model.take(),
+            tool_calls: std::mem::take(&mut entry.tool_calls),
+            timestamp: entry.timestamp,
+        });
+        answers.sort_by_key(|a| a.timestamp);
+
+        let current = answers
+            .iter()
+            .position(|a| a.timestamp == entry.timestamp)
+            .unwrap_or_default();
+        let next = (current + 1) % answers.len();
+
+        let shown = answers.remove(next);
+        entry.content = shown.content;
+        entry.model = shown.model;
+        entry.tool_calls = shown.tool_calls;
+        entry.timestamp = shown.timestamp;
+        entry.alternates = answers;
+
+        if let Some(model) = &entry.model {
+            self.model = model.to_owned();
+        }
+
+        Some((next + 1, entry.alternates.len() + 1))
+    }
+}
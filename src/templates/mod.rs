@@ -0,0 +1,174 @@
+use std::{env, fs, path::PathBuf, process::Command};
+
+use console::style;
+
+use crate::utils::{commands::read_clipboard, paths::config_dir};
+
+/// A slash command defined by the user in `<config dir>/commands/<name>.md`.
+///
+/// The file is the prompt that is sent, with optional front matter:
+///
+/// ```text
+/// ---
+/// description: Review the current changes
+/// model: gpt-4.1
+/// ---
+/// Review this diff, focusing on {{args}}:
+///
+/// {{git_diff}}
+/// ```
+pub struct Template {
+    pub name: String,
+    pub description: Option<String>,
+    /// Model that answers instead of the active one
+    pub model: Option<String>,
+    body: String,
+}
+
+impl Template {
+    /// Templates in the commands directory, sorted by name. Files that
+    /// can't be read are reported and skipped.
+    pub fn load_all() -> Vec<Template> {
+        let Some(entries) = commands_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+            return vec![];
+        };
+
+        let mut templates = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_str()?.to_string();
+                if name.is_empty() || name.contains(char::is_whitespace) {
+                    return None;
+                }
+
+                match fs::read_to_string(&path) {
+                    Ok(content) => Some(Self::parse(name, &content)),
+                    Err(e) => {
+                        eprintln!(
+                            "{} Failed to read {}: {e}",
+                            style("✗").red(),
+                            path.display()
+                        );
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        templates
+    }
+
+    fn parse(name: String, content: &str) -> Self {
+        let mut template = Self {
+            name,
+            description: None,
+            model: None,
+            body: content.trim().to_string(),
+        };
+
+        let content = content.replace("\r\n", "\n");
+        let Some(rest) = content.strip_prefix("---\n") else {
+            return template;
+        };
+        let Some((front_matter, body)) = rest.split_once("\n---") else {
+            return template;
+        };
+
+        for line in front_matter.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            match key.trim() {
+                "description" => template.description = value,
+                "model" => template.model = value,
+                _ => {}
+            }
+        }
+        template.body = body.trim().to_string();
+
+        template
+    }
+
+    pub fn takes_args(&self) -> bool {
+        self.body.contains("{{args}}")
+    }
+
+    /// The prompt with its placeholders filled in:
+    ///
+    /// - `{{args}}`: what was typed after the command
+    /// - `{{clipboard}}`: the clipboard contents
+    /// - `{{git_diff}}`: uncommitted changes in the working directory
+    /// - `{{file:<path>}}`: a file, attached like an `@path` mention
+    /// - `{{env:<name>}}`: an environment variable
+    ///
+    /// Arguments given to a template without `{{args}}` go at the end.
+    pub fn expand(&self, args: &str) -> Result<String, String> {
+        let mut prompt = String::with_capacity(self.body.len());
+        let mut rest = self.body.as_str();
+
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+                break;
+            };
+
+            prompt.push_str(&rest[..start]);
+            let placeholder = rest[start + 2..end].trim();
+            prompt.push_str(&self.placeholder(placeholder, args)?);
+            rest = &rest[end + 2..];
+        }
+        prompt.push_str(rest);
+
+        if !self.takes_args() && !args.is_empty() {
+            prompt.push_str("\n\n");
+            prompt.push_str(args);
+        }
+
+        Ok(prompt)
+    }
+
+    fn placeholder(&self, placeholder: &str, args: &str) -> Result<String, String> {
+        let value = match placeholder.split_once(':') {
+            None if placeholder == "args" => args.to_string(),
+            None if placeholder == "clipboard" => read_clipboard()?,
+            None if placeholder == "git_diff" => git_diff()?,
+            Some(("file", path)) => format!("@{}", path.trim()),
+            Some(("env", name)) => env::var(name.trim()).unwrap_or_default(),
+            _ => {
+                return Err(format!(
+                    "Unknown placeholder {{{{{placeholder}}}}} in /{}",
+                    self.name
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+/// Where user commands are read from
+pub fn commands_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("commands"))
+}
+
+/// Staged and unstaged changes against the last commit
+fn git_diff() -> Result<String, String> {
+    let output = Command::new("git")
+        .args(["diff", "HEAD"])
+        .output()
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git diff failed: {}", error.trim()));
+    }
+
+    let diff = String::from_utf8_lossy(&output.stdout).into_owned();
+    match diff.trim().is_empty() {
+        true => Err("There are no uncommitted changes".to_string()),
+        false => Ok(diff),
+    }
+}
@@ -0,0 +1,416 @@
+use std::{
+    fmt::{self, Write},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use globset::Glob;
+use ignore::WalkBuilder;
+use regex::Regex;
+use rig::{completion::ToolDefinition, tool::Tool};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::attachments::is_ignored;
+
+/// Maximum size of a tool result
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+/// Maximum number of paths returned by `list_directory` and `glob`
+const MAX_PATHS: usize = 500;
+/// Maximum number of lines returned by `grep`
+const MAX_MATCHES: usize = 200;
+
+/// Directories the filesystem tools may read from
+#[derive(Clone)]
+pub struct Scope {
+    roots: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+pub struct FsError(String);
+
+impl fmt::Display for FsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for FsError {}
+
+impl Scope {
+    /// Roots that don't exist are left out, they are validated when set
+    pub fn new(roots: &[PathBuf]) -> Self {
+        Self {
+            roots: roots
+                .iter()
+                .filter_map(|root| root.canonicalize().ok())
+                .collect(),
+        }
+    }
+
+    /// Resolve a path given by the model. Relative paths start at the first
+    /// root, and the result must be inside a root and not ignored by git.
+    fn resolve(&self, path: Option<&str>) -> Result<PathBuf, FsError> {
+        let first = self
+            .roots
+            .first()
+            .ok_or_else(|| FsError("No readable directories are configured".to_string()))?;
+
+        let path = match path.map(str::trim).filter(|p| !p.is_empty()) {
+            Some(path) => first.join(path),
+            None => first.to_owned(),
+        };
+        let path = path
+            .canonicalize()
+            .map_err(|e| FsError(format!("{}: {e}", path.display())))?;
+
+        let Some(root) = self.roots.iter().find(|root| path.starts_with(root)) else {
+            return Err(FsError(format!(
+                "{} is outside the readable directories: {}",
+                path.display(),
+                self.describe()
+            )));
+        };
+
+        // Check every level below the root, an ignored directory hides its contents
+        let mut current = root.to_owned();
+        for component in path.strip_prefix(root).unwrap_or(&path).components() {
+            current.push(component);
+            if is_ignored(&current) {
+                return Err(FsError(format!(
+                    "{} is ignored by .gitignore",
+                    current.display()
+                )));
+            }
+        }
+
+        Ok(path)
+    }
+
+    fn describe(&self) -> String {
+        self.roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Files under `dir` that are not ignored, sorted by path
+    fn walk(&self, dir: &Path) -> impl Iterator<Item = PathBuf> {
+        WalkBuilder::new(dir)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+            .map(|e| e.into_path())
+    }
+
+    /// Paths are shown relative to their root
+    fn display(&self, path: &Path) -> String {
+        self.roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+#[derive(Clone)]
+pub struct ReadFile(pub Scope);
+
+#[derive(Deserialize)]
+pub struct ReadFileArgs {
+    path: String,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+}
+
+impl Tool for ReadFile {
+    const NAME: &'static str = "read_file";
+
+    type Error = FsError;
+    type Args = ReadFileArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Read a text file, optionally only a range of lines. Lines are prefixed with \
+                 their number. Readable directories: {}",
+                self.0.describe()
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the file, relative to the first readable directory or absolute"
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "First line to read, starting at 1"
+                    },
+                    "end_line": {
+                        "type": "integer",
+                        "description": "Last line to read (inclusive)"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = self.0.resolve(Some(&args.path))?;
+        let bytes = fs::read(&path).map_err(|e| FsError(format!("{}: {e}", args.path)))?;
+        if bytes.contains(&0) {
+            return Err(FsError(format!("{} is a binary file", args.path)));
+        }
+
+        let content = String::from_utf8_lossy(&bytes);
+        let start = args.start_line.unwrap_or(1).max(1);
+        let end = args.end_line.unwrap_or(usize::MAX);
+
+        let mut output = String::new();
+        for (number, line) in content.lines().enumerate().map(|(i, l)| (i + 1, l)) {
+            if number < start {
+                continue;
+            }
+            if number > end {
+                break;
+            }
+            if output.len() + line.len() > MAX_OUTPUT_BYTES {
+                let _ = write!(
+                    output,
+                    "[cut off at line {number}, read the rest with start_line]"
+                );
+                break;
+            }
+            let _ = writeln!(output, "{number}: {line}");
+        }
+
+        Ok(output)
+    }
+}
+
+#[derive(Clone)]
+pub struct ListDirectory(pub Scope);
+
+#[derive(Deserialize)]
+pub struct ListDirectoryArgs {
+    path: Option<String>,
+}
+
+impl Tool for ListDirectory {
+    const NAME: &'static str = "list_directory";
+
+    type Error = FsError;
+    type Args = ListDirectoryArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "List the files and directories in a directory. Directories end with a slash. \
+                 Readable directories: {}",
+                self.0.describe()
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path of the directory, the first readable directory if left out"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let dir = self.0.resolve(args.path.as_deref())?;
+
+        let entries = WalkBuilder::new(&dir)
+            .max_depth(Some(1))
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.depth() == 1)
+            .map(|e| match e.file_type().is_some_and(|t| t.is_dir()) {
+                true => format!("{}/", e.file_name().to_string_lossy()),
+                false => e.file_name().to_string_lossy().into_owned(),
+            });
+
+        Ok(limit_lines(entries, MAX_PATHS, "entries"))
+    }
+}
+
+#[derive(Clone)]
+pub struct GlobFiles(pub Scope);
+
+#[derive(Deserialize)]
+pub struct GlobArgs {
+    pattern: String,
+    path: Option<String>,
+}
+
+impl Tool for GlobFiles {
+    const NAME: &'static str = "glob";
+
+    type Error = FsError;
+    type Args = GlobArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Find files by glob pattern, e.g. **/*.toml. Readable directories: {}",
+                self.0.describe()
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Glob pattern matched against paths relative to the searched directory"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Directory to search, the first readable directory if left out"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let dir = self.0.resolve(args.path.as_deref())?;
+        let matcher = Glob::new(&args.pattern)
+            .map_err(|e| FsError(e.to_string()))?
+            .compile_matcher();
+
+        let paths = self
+            .0
+            .walk(&dir)
+            .filter(|path| matcher.is_match(path.strip_prefix(&dir).unwrap_or(path)))
+            .map(|path| self.0.display(&path));
+
+        Ok(limit_lines(paths, MAX_PATHS, "files"))
+    }
+}
+
+#[derive(Clone)]
+pub struct Grep(pub Scope);
+
+#[derive(Deserialize)]
+pub struct GrepArgs {
+    pattern: String,
+    path: Option<String>,
+    glob: Option<String>,
+}
+
+impl Tool for Grep {
+    const NAME: &'static str = "grep";
+
+    type Error = FsError;
+    type Args = GrepArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Search text files for a regular expression. Returns matching lines as \
+                 path:line: text. Readable directories: {}",
+                self.0.describe()
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regular expression (Rust regex syntax)"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File or directory to search, the first readable directory if left out"
+                    },
+                    "glob": {
+                        "type": "string",
+                        "description": "Only search files matching this glob pattern, e.g. *.rs"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = self.0.resolve(args.path.as_deref())?;
+        let regex = Regex::new(&args.pattern).map_err(|e| FsError(e.to_string()))?;
+        let filter = match &args.glob {
+            Some(glob) => Some(
+                Glob::new(glob)
+                    .map_err(|e| FsError(e.to_string()))?
+                    .compile_matcher(),
+            ),
+            None => None,
+        };
+
+        let files: Box<dyn Iterator<Item = PathBuf>> = match path.is_dir() {
+            true => Box::new(self.0.walk(&path)),
+            false => Box::new(std::iter::once(path.to_owned())),
+        };
+
+        let matches = files
+            .filter(|file| {
+                filter.as_ref().is_none_or(|glob| {
+                    glob.is_match(file.strip_prefix(&path).unwrap_or(file))
+                        || file.file_name().is_some_and(|name| glob.is_match(name))
+                })
+            })
+            .filter_map(|file| {
+                let content = fs::read(&file).ok().filter(|bytes| !bytes.contains(&0))?;
+                let content = String::from_utf8_lossy(&content).into_owned();
+                Some((file, content))
+            })
+            .flat_map(|(file, content)| {
+                let display = self.0.display(&file);
+                content
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| regex.is_match(line))
+                    .map(|(i, line)| format!("{display}:{}: {}", i + 1, line.trim_end()))
+                    .collect::<Vec<_>>()
+            });
+
+        Ok(limit_lines(matches, MAX_MATCHES, "matches"))
+    }
+}
+
+/// Join lines up to a count and size limit and say when some were left out
+fn limit_lines(lines: impl Iterator<Item = String>, max: usize, what: &str) -> String {
+    let mut output = String::new();
+    let mut count = 0;
+
+    for line in lines {
+        if count == max || output.len() + line.len() > MAX_OUTPUT_BYTES {
+            let _ = write!(output, "[more {what} left out]");
+            return output;
+        }
+        let _ = writeln!(output, "{line}");
+        count += 1;
+    }
+
+    if count == 0 {
+        return format!("No {what} found");
+    }
+
+    output
+}
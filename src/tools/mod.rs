@@ -0,0 +1,104 @@
+pub mod fs;
+pub mod shell;
+
+use rig::{
+    agent::AgentBuilder,
+    completion::{CompletionModel, ToolDefinition},
+    tool::Tool,
+};
+
+use fs::{GlobFiles, Grep, ListDirectory, ReadFile, Scope};
+use shell::{ShellArgs, ShellTool};
+
+/// Tools that ship with termai, as opposed to tools from MCP servers
+#[derive(Clone, Default)]
+pub struct BuiltinTools {
+    pub shell: Option<ShellTool>,
+    /// Read-only filesystem tools, limited to these directories
+    pub fs: Option<Scope>,
+}
+
+/// What happens to a tool call before it runs
+pub enum Approval {
+    /// Run without asking
+    Allow,
+    /// Ask the user first, with this prompt
+    Ask(String),
+    /// Refuse, with the reason that goes back to the model
+    Deny(String),
+}
+
+impl BuiltinTools {
+    pub fn add_tools<M: CompletionModel>(
+        &self,
+        mut agent_builder: AgentBuilder<M>,
+    ) -> AgentBuilder<M> {
+        if let Some(shell) = &self.shell {
+            agent_builder = agent_builder.tool(shell.clone());
+        }
+
+        if let Some(scope) = &self.fs {
+            agent_builder = agent_builder
+                .tool(ReadFile(scope.clone()))
+                .tool(ListDirectory(scope.clone()))
+                .tool(GlobFiles(scope.clone()))
+                .tool(Grep(scope.clone()));
+        }
+
+        agent_builder
+    }
+
+    /// Names of the enabled tools. MCP tools with these names are left out,
+    /// so a call by one of them always reaches the built-in tool.
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = vec![];
+        if self.shell.is_some() {
+            names.push(ShellTool::NAME);
+        }
+        if self.fs.is_some() {
+            names.extend([
+                ReadFile::NAME,
+                ListDirectory::NAME,
+                GlobFiles::NAME,
+                Grep::NAME,
+            ]);
+        }
+        names
+    }
+
+    /// Definitions of the tools, as the model receives them
+    pub async fn definitions(&self) -> Vec<ToolDefinition> {
+        let mut definitions = vec![];
+
+        if let Some(shell) = &self.shell {
+            definitions.push(shell.definition(String::new()).await);
+        }
+
+        if let Some(scope) = &self.fs {
+            definitions.push(ReadFile(scope.clone()).definition(String::new()).await);
+            definitions.push(ListDirectory(scope.clone()).definition(String::new()).await);
+            definitions.push(GlobFiles(scope.clone()).definition(String::new()).await);
+            definitions.push(Grep(scope.clone()).definition(String::new()).await);
+        }
+
+        definitions
+    }
+
+    pub fn approval(&self, name: &str, arguments: &serde_json::Value) -> Approval {
+        match (name, &self.shell) {
+            (ShellTool::NAME, Some(shell)) => {
+                match serde_json::from_value::<ShellArgs>(arguments.to_owned()) {
+                    Ok(args) => shell.approval(&args.command),
+                    Err(_) => Approval::Deny("Invalid arguments".to_string()),
+                }
+            }
+            // Read-only and limited to the configured directories
+            (ReadFile::NAME | ListDirectory::NAME | GlobFiles::NAME | Grep::NAME, _)
+                if self.fs.is_some() =>
+            {
+                Approval::Allow
+            }
+            _ => Approval::Ask(format!("Run tool '{name}'?")),
+        }
+    }
+}
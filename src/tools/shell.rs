@@ -0,0 +1,172 @@
+use std::{fmt, process::Stdio, time::Duration};
+
+use globset::{Glob, GlobMatcher};
+use rig::{completion::ToolDefinition, tool::Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::utils::shell::shell_command;
+
+use super::Approval;
+
+/// Runs commands in the user's shell
+#[derive(Clone)]
+pub struct ShellTool {
+    timeout: Duration,
+    output_limit: usize,
+    /// Commands that run without asking
+    allow: Vec<GlobMatcher>,
+    /// Commands that never run
+    deny: Vec<GlobMatcher>,
+    /// A shell_deny pattern that doesn't compile. Every command needs
+    /// approval then, since the pattern may have been meant to block it.
+    invalid_deny: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ShellArgs {
+    pub command: String,
+}
+
+#[derive(Serialize)]
+pub struct ShellOutput {
+    /// Missing when the command was killed
+    exit_code: Option<i32>,
+    stdout: String,
+    stderr: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    timed_out: bool,
+}
+
+#[derive(Debug)]
+pub struct ShellError(String);
+
+impl fmt::Display for ShellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ShellError {}
+
+impl ShellTool {
+    /// Patterns are validated when set, but the config file can be edited by
+    /// hand. Invalid allow patterns match nothing, and an invalid deny pattern
+    /// makes every command ask.
+    pub fn new(timeout: Duration, output_limit: usize, allow: &[String], deny: &[String]) -> Self {
+        let invalid_deny = deny.iter().find(|p| pattern(p).is_err()).cloned();
+
+        Self {
+            timeout,
+            output_limit,
+            allow: allow.iter().filter_map(|p| pattern(p).ok()).collect(),
+            deny: deny.iter().filter_map(|p| pattern(p).ok()).collect(),
+            invalid_deny,
+        }
+    }
+
+    pub fn approval(&self, command: &str) -> Approval {
+        let command = command.trim();
+        if self.deny.iter().any(|p| p.is_match(command)) {
+            Approval::Deny(format!(
+                "The command `{command}` is blocked by the user's shell_deny setting"
+            ))
+        } else if let Some(invalid) = &self.invalid_deny {
+            Approval::Ask(format!(
+                "Run `{command}`? (shell_deny has the invalid pattern `{invalid}`, so every command needs approval)"
+            ))
+        } else if !has_control_syntax(command) && self.allow.iter().any(|p| p.is_match(command)) {
+            Approval::Allow
+        } else {
+            Approval::Ask(format!("Run `{command}`?"))
+        }
+    }
+}
+
+impl Tool for ShellTool {
+    const NAME: &'static str = "shell";
+
+    type Error = ShellError;
+    type Args = ShellArgs;
+    type Output = ShellOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Run a command in the user's shell, in the current working directory, and return \
+                 its exit code, stdout and stderr. The user approves every command. Commands are \
+                 killed after {} seconds and output is cut off at {} KB.",
+                self.timeout.as_secs(),
+                self.output_limit / 1024
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The command to run"
+                    }
+                },
+                "required": ["command"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if let Approval::Deny(reason) = self.approval(&args.command) {
+            return Err(ShellError(reason));
+        }
+
+        let mut command = tokio::process::Command::from(shell_command(&args.command));
+        let child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ShellError(format!("Failed to start the shell: {e}")))?;
+
+        match tokio::time::timeout(self.timeout, child.wait_with_output()).await {
+            Ok(Ok(output)) => Ok(ShellOutput {
+                exit_code: output.status.code(),
+                stdout: truncate(&output.stdout, self.output_limit),
+                stderr: truncate(&output.stderr, self.output_limit),
+                timed_out: false,
+            }),
+            Ok(Err(e)) => Err(ShellError(format!("Failed to run the command: {e}"))),
+            Err(_) => Ok(ShellOutput {
+                exit_code: None,
+                stdout: String::new(),
+                stderr: format!("Killed after {} seconds", self.timeout.as_secs()),
+                timed_out: true,
+            }),
+        }
+    }
+}
+
+/// Glob pattern matched against the whole command, `*` also matches `/`
+pub fn pattern(pattern: &str) -> Result<GlobMatcher, String> {
+    Glob::new(pattern.trim())
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| e.to_string())
+}
+
+/// Commands that chain, pipe, substitute or redirect are never allowed by a
+/// pattern, since `git *` would also match `git status; rm -rf ~`
+fn has_control_syntax(command: &str) -> bool {
+    command.contains([';', '&', '|', '`', '>', '<', '\n', '\r']) || command.contains("$(")
+}
+
+fn truncate(bytes: &[u8], limit: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= limit {
+        return text.into_owned();
+    }
+
+    let mut end = limit;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n[output cut off at {} KB]", &text[..end], limit / 1024)
+}
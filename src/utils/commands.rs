@@ -47,3 +47,31 @@ pub fn copy_to_clipboard(input: &str) -> Result<(), &'static str> {
 
     Ok(())
 }
+
+pub fn read_clipboard() -> Result<String, &'static str> {
+    let output = if cfg!(target_os = "windows") {
+        Command::new("powershell")
+            .args(["-NoProfile", "-Command", "Get-Clipboard"])
+            .output()
+            .map_err(|_| "Failed to start powershell to read the clipboard.")?
+    } else if cfg!(target_os = "linux") {
+        Command::new("xclip")
+            .args(["-selection", "clipboard", "-o"])
+            .output()
+            .map_err(|_| "Failed to start xclip. Ensure it is installed using 'sudo apt install xclip' or 'sudo yum install xclip'.")?
+    } else if cfg!(target_os = "macos") {
+        Command::new("pbpaste")
+            .output()
+            .map_err(|_| "Failed to start pbpaste. Ensure it is available on your system.")?
+    } else {
+        return Err(
+            "Unsupported OS. Clipboard functionality is not implemented for this platform.",
+        );
+    };
+
+    if !output.status.success() {
+        return Err("Failed to read the clipboard");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
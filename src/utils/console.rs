@@ -2,6 +2,8 @@ use console::{style, Style};
 use dialoguer::theme::{ColorfulTheme, Theme};
 use indicatif::ProgressStyle;
 
+use crate::client::ChatError;
+
 pub fn get_spinner_style() -> ProgressStyle {
     ProgressStyle::default_spinner()
         .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏")
@@ -21,3 +23,12 @@ pub fn get_select_theme() -> impl Theme {
         ..Default::default()
     }
 }
+
+/// The error with what to do about it on the next line
+pub fn format_error(error: &ChatError) -> String {
+    let message = format!("{} {error}", style("✗").red());
+    match error.hint() {
+        Some(hint) => format!("{message}\n  {}", style(hint).dim()),
+        None => message,
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    LazyLock,
+};
+
+use tokio::sync::Notify;
+
+/// A generation is running, so Ctrl-C cancels it instead of quitting
+static GENERATING: AtomicBool = AtomicBool::new(false);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static NOTIFY: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Marks a running generation until it is dropped
+pub struct Generation;
+
+impl Generation {
+    pub fn start() -> Self {
+        INTERRUPTED.store(false, Ordering::SeqCst);
+        GENERATING.store(true, Ordering::SeqCst);
+        Self
+    }
+
+    /// Resolves once the generation is interrupted
+    pub async fn interrupted(&self) {
+        loop {
+            let notified = NOTIFY.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        INTERRUPTED.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Generation {
+    fn drop(&mut self) {
+        GENERATING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Cancel the running generation. Returns false when there is none.
+pub fn interrupt() -> bool {
+    if !GENERATING.load(Ordering::SeqCst) {
+        return false;
+    }
+
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    NOTIFY.notify_waiters();
+    true
+}
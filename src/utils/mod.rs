@@ -3,4 +3,6 @@ pub mod commands;
 pub mod console;
 pub mod encryption;
 pub mod enums;
+pub mod interrupt;
+pub mod paths;
 pub mod shell;
@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+
+/// Directory for data that is not configuration, such as saved chat sessions.
+/// Uses the same project identifiers as `confy`.
+pub fn data_dir() -> Option<PathBuf> {
+    ProjectDirs::from("rs", "", "termai").map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Directory of the configuration file, where user commands live too
+pub fn config_dir() -> Option<PathBuf> {
+    ProjectDirs::from("rs", "", "termai").map(|dirs| dirs.config_dir().to_path_buf())
+}
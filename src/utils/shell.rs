@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, process::Command};
 use sysinfo::{Pid, System};
 
 pub fn detect_shell_environment() -> Option<(String, &'static str)> {
@@ -36,3 +36,23 @@ pub fn detect_shell_environment() -> Option<(String, &'static str)> {
 
     Some((shell.into(), os))
 }
+
+/// A command that runs `script` in the user's shell
+pub fn shell_command(script: &str) -> Command {
+    let shell = detect_shell_environment()
+        .map(|(shell, _)| shell)
+        .unwrap_or_else(|| match cfg!(target_os = "windows") {
+            true => "cmd".into(),
+            false => "sh".into(),
+        });
+
+    let flag = match shell.to_lowercase().as_str() {
+        "cmd" => "/C",
+        "powershell" | "pwsh" => "-Command",
+        _ => "-c",
+    };
+
+    let mut command = Command::new(shell);
+    command.arg(flag).arg(script);
+    command
+}